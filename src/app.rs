@@ -1,12 +1,23 @@
 extern crate nalgebra as na;
+use crate::art_placement::*;
 use crate::artwork::*;
+use crate::async_tasks::AsyncPayload;
 use crate::error::*;
+use crate::garment::*;
+use crate::halftone::*;
 use crate::icons::*;
 use crate::loaded_image::*;
 use crate::math::*;
 use crate::movement_state::MovementState;
 use crate::notice_panel::*;
+use crate::print_area::*;
+use crate::report_export::*;
+use crate::report_html::*;
+use crate::report_settings::*;
 use crate::report_templates::*;
+use crate::shirt_colors::*;
+use crate::shirt_comparison::*;
+use crate::shirt_contrast::*;
 use crate::tool_select::*;
 use crate::tshirt_storage::*;
 use egui_extras::{Size, StripBuilder};
@@ -18,14 +29,44 @@ const REPORT_TEXT_WIDTH: f32 = 150.0;
 const REPORT_METRIC_WIDTH: f32 = 40.0;
 const REPORT_PERCENT_WIDTH: f32 = 25.0;
 const BUTTON_WIDTH: f32 = 80.0;
+const THUMBNAIL_WIDTH: f32 = 72.0;
+
+// Art can't be placed any narrower than this, in inches
+const MIN_ART_WIDTH: f32 = 0.25;
+
+// Keys used to persist the report settings, shirt colors and garments between runs
+const SETTINGS_KEY: &str = "report_settings";
+const CUSTOM_COLORS_KEY: &str = "custom_shirt_colors";
+const SHIRT_CATALOG_KEY: &str = "shirt_catalog";
+const GARMENTS_KEY: &str = "garments";
+
+// The corners of the art in the art's space, clockwise from the top left
+const UNIT_SQUARE_CORNERS: [(f32, f32); 4] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+
+// Thin line widths offered in the settings window, as fractions of an inch
+const THIN_LINE_FRACTIONS: [u32; 6] = [16, 32, 48, 64, 96, 128];
+// Specks are flagged if they're smaller than a square 1/n of an inch on a side
+const SPECK_FRACTIONS: [u32; 5] = [16, 24, 32, 48, 64];
+
+// How the art is previewed on dark shirts.  Direct to garment printers put down a
+// white underbase first, or the colors come out muddy.
+//
+#[derive(PartialEq, Copy, Clone)]
+enum DtgPreview {
+    Off,
+    NoUnderbase,
+    Underbase,
+}
 
 // State for the TShirt Artwork Checker app
 //
 pub struct TShirtCheckerApp {
     // Storage for artwork the user may want to put on the t-shiurt
     art_storage: ArtStorage,
-    // Which of the 4 pieces of artwork from art_storage is currently selected.
+    // Which of the pieces of artwork for the front of the shirt is currently selected.
     selected_art_id: ArtEnum,
+    // Which side of the t-shirt is being displayed.  The back has its own art slot.
+    selected_side: ShirtSide,
     // Storage for icons used by the app
     icons: IconStorage,
     // Persistant state used to move or zoom the t-shirt and artwork
@@ -34,8 +75,29 @@ pub struct TShirtCheckerApp {
     tshirt_image_storage: TShirtStorage,
     // Color of the tshirt that's currently selected
     selected_tshirt: TShirtColors,
+    // The color being edited in the custom shirt color picker, and its hex text
+    custom_color: egui::Color32,
+    custom_color_hex: String,
+    // Is the print area on the selected garment mockup being calibrated?
+    calibrating: bool,
+    // Are the handles for moving, scaling and rotating the art showing?
+    placing_art: bool,
+    // Is the art previewed as a DTG print?  Only used on dark shirts.
+    dtg_preview: DtgPreview,
     // Template storage for the different tshirt arts report types
     report_templates: ReportTemplates,
+    // User adjustable report thresholds.  Persisted between runs.
+    settings: ReportSettings,
+    // Is the report settings window open?
+    settings_open: bool,
+    // Is the shirt comparison window open?
+    comparing: bool,
+    // Is the partial transparency fix window open?
+    fixing_alpha: bool,
+    // Is the halftone fix window open?
+    halftoning: bool,
+    // Is the art previewed with the shirt color knocked out?
+    knockout_preview: bool,
     // Sender and receiver for image data that's computed asyncronously to improve load times
     async_data_to_app_sender: crate::async_tasks::AsyncImageSender,
     async_data_to_app_receiver: crate::async_tasks::AsyncImageReceiver,
@@ -80,9 +142,35 @@ impl eframe::App for TShirtCheckerApp {
     //
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let changes = self.paint_all_panels(ctx);
-        self.journal_changes_to_app_state(changes);
+        self.journal_changes_to_app_state(ctx, changes);
         self.schedule_repaint_request_if_needed(ctx);
     }
+
+    //
+    // Eframe's hook for saving state between runs.
+    //
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SETTINGS_KEY, &self.settings);
+        let custom_colors: Vec<String> = self
+            .tshirt_image_storage
+            .custom_colors()
+            .into_iter()
+            .map(shirt_color_to_hex)
+            .collect();
+        eframe::set_value(storage, CUSTOM_COLORS_KEY, &custom_colors);
+        eframe::set_value(
+            storage,
+            SHIRT_CATALOG_KEY,
+            self.tshirt_image_storage.catalog(),
+        );
+        let garments: Vec<SavedGarment> = self
+            .tshirt_image_storage
+            .garments()
+            .iter()
+            .map(Garment::to_saved)
+            .collect();
+        eframe::set_value(storage, GARMENTS_KEY, &garments);
+    }
 }
 
 impl TShirtCheckerApp {
@@ -94,13 +182,52 @@ impl TShirtCheckerApp {
         // load the default artwork.  This will involved 3 in memory PNG decompressions,
         // which isn't super best practice runtime wise. TODO, measure actual time.
         //
-        let art_storage = ArtStorage::new(&cc.egui_ctx);
+        let art_storage = ArtStorage::new();
 
         //
         // Choose the artwork in "slot 0" to be the initial selected art.
         //
         let selected_art_id = ArtEnum::Artwork0;
 
+        //
+        // Restore the report settings from the last run, if there was one.
+        //
+        let settings: ReportSettings = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, SETTINGS_KEY))
+            .unwrap_or_default();
+
+        //
+        // Recreate the t-shirts for the shirt color catalog, any custom colors the
+        // user picked last run, and any garments they imported.
+        //
+        let catalog: ShirtColorCatalog = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, SHIRT_CATALOG_KEY))
+            .unwrap_or_default();
+        let mut tshirt_image_storage = TShirtStorage::new(catalog);
+        let custom_colors: Vec<String> = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, CUSTOM_COLORS_KEY))
+            .unwrap_or_default();
+        for color in custom_colors
+            .iter()
+            .filter_map(|hex| shirt_color_from_hex(hex))
+        {
+            tshirt_image_storage.add_custom_color(color);
+        }
+        let garments: Vec<SavedGarment> = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, GARMENTS_KEY))
+            .unwrap_or_default();
+        for garment in garments
+            .into_iter()
+            .filter_map(|saved| Garment::from_saved(saved).ok())
+        {
+            tshirt_image_storage.add_garment(garment);
+        }
+        let custom_color = egui::Color32::from_rgb(0, 143, 190);
+
         //
         // Create a pipe that will be used by computationally heavy tasks so we don't
         // block the main thread during updates
@@ -116,17 +243,31 @@ impl TShirtCheckerApp {
             &cc.egui_ctx,
             art_storage.get_art(selected_art_id),
             selected_art_id,
+            None,
+            &settings,
             &async_data_to_app_sender,
         );
 
         Self {
             art_storage,
             selected_art_id,
-            tshirt_image_storage: TShirtStorage::new(&cc.egui_ctx),
+            selected_side: ShirtSide::Front,
+            tshirt_image_storage,
             move_state: MovementState::new(),
             icons: IconStorage::new(&cc.egui_ctx),
-            selected_tshirt: TShirtColors::Red,
+            selected_tshirt: TShirtColors::Catalog(0),
+            custom_color,
+            custom_color_hex: shirt_color_to_hex(custom_color),
+            calibrating: false,
+            placing_art: false,
+            dtg_preview: DtgPreview::Off,
             report_templates: ReportTemplates::new(),
+            settings,
+            settings_open: false,
+            comparing: false,
+            fixing_alpha: false,
+            halftoning: false,
+            knockout_preview: false,
             selected_tool: ToolSelection::new(),
             async_data_to_app_sender,
             async_data_to_app_receiver,
@@ -141,6 +282,10 @@ impl TShirtCheckerApp {
         self.paint_bottom_panel(ctx);
         self.paint_right_panel(&mut changes, ctx);
         self.paint_central_panel(&mut changes, ctx);
+        self.paint_settings_window(&mut changes, ctx);
+        self.paint_comparison_window(&mut changes, ctx);
+        self.paint_alpha_fix_window(&mut changes, ctx);
+        self.paint_halftone_window(&mut changes, ctx);
         changes
     }
 
@@ -191,7 +336,7 @@ impl TShirtCheckerApp {
         let screen = ctx.screen_rect();
         let size = screen.max - screen.min;
         let scale_x = (size.x * 0.33) / 260.0;
-        let scale_y = (size.y - 150.0) / 700.0;
+        let scale_y = (size.y - 150.0) / 1000.0;
         let scale = scale_x.min(scale_y).clamp(0.20, 1.0);
 
         let targetx = 50.0 + 260.0 * scale;
@@ -209,7 +354,17 @@ impl TShirtCheckerApp {
 
                     ui.horizontal(|ui| {
                         self.paint_import_button(ui, ctx, scale);
-                        self.paint_partial_transparency_fix_button(ui, ctx, scale);
+                        self.paint_partial_transparency_fix_button(changes, ui, scale);
+                        self.paint_export_button(ui, ctx, scale);
+                        ui.vertical(|ui| {
+                            self.paint_halftone_button(changes, ui);
+                            self.paint_defringe_button(ui, ctx);
+                            self.paint_crop_margins_button(ui, ctx);
+                            self.paint_thin_line_fix_button(ui, ctx);
+                            self.paint_speck_fix_button(ui, ctx);
+                            self.paint_background_fix_button(changes, ui);
+                            self.paint_knockout_button(changes, ui);
+                        });
                     });
                 })
             });
@@ -234,18 +389,62 @@ impl TShirtCheckerApp {
         Self::paint_panel_separator(ui, scale);
     }
 
-    // Display the 5 reports for the selected artwork
+    // Display the reports for the selected artwork
     //
-    fn paint_reports(&self, changes: &mut ChangesToBeMade, ui: &mut egui::Ui, scale: f32) {
-        self.paint_report(changes, ui, scale, ReportTypes::Dpi);
-        self.paint_report(changes, ui, scale, ReportTypes::AreaUsed);
-        self.paint_report(changes, ui, scale, ReportTypes::Bib);
-        self.paint_report(changes, ui, scale, ReportTypes::ThinLines);
-        self.paint_report(changes, ui, scale, ReportTypes::PartialTransparency);
+    fn paint_reports(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui, scale: f32) {
+        for report_type in ReportTypes::ALL {
+            self.paint_report(changes, ui, scale, report_type);
+        }
+        ui.horizontal(|ui| {
+            self.paint_print_area_select(changes, ui);
+            if ui
+                .small_button("Thresholds...")
+                .on_hover_text("Change the pass/ warn/ fail thresholds for the reports.")
+                .clicked()
+            {
+                changes += Box::new(|app: &mut Self| app.settings_open = !app.settings_open);
+            }
+            if ui
+                .small_button("Compare shirts...")
+                .on_hover_text("See how every piece of art does on every shirt color.")
+                .clicked()
+            {
+                let ctx = ui.ctx().clone();
+                changes += Box::new(move |app: &mut Self| {
+                    app.comparing = !app.comparing;
+                    app.cache_in_missing_dependent_data(&ctx);
+                });
+            }
+        });
 
         Self::paint_panel_separator(ui, scale);
     }
 
+    // Choose where on the t-shirt the art gets printed.  Changing the print area
+    // changes the DPI, so every report gets recomputed.
+    //
+    fn paint_print_area_select(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui) {
+        let side = self.selected_side;
+        let mut print_area = self.selected_print_area();
+        egui::ComboBox::from_id_source("print_area")
+            .selected_text(print_area.name())
+            .show_ui(ui, |ui| {
+                for choice in PrintArea::ALL {
+                    if choice.side() == side {
+                        ui.selectable_value(&mut print_area, choice, choice.name());
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Where on the T-Shirt the artwork is printed.");
+        if print_area != self.selected_print_area() {
+            let ctx = ui.ctx().clone();
+            let mut settings = self.settings.clone();
+            *settings.print_area_for_mut(side) = print_area;
+            changes += Box::new(move |app: &mut Self| app.set_settings(settings.clone(), &ctx));
+        }
+    }
+
     // Paint one report line
     //
     fn paint_report(
@@ -257,7 +456,10 @@ impl TShirtCheckerApp {
     ) {
         let report_template = self.report_templates.report_type_to_template(report_type);
         let art = self.get_selected_art();
+        let placement = self.selected_placement();
+        let shirt_color = self.selected_shirt_color();
         let dependent_data = self.get_selected_dependent_data();
+        let thresholds = self.settings.thresholds(report_type);
 
         // Column 1 - Name of the report
         let report_name = mtexts(&report_template.label, scale);
@@ -265,11 +467,17 @@ impl TShirtCheckerApp {
         // Column 2 - The status of the report (i.e., pass/ warn, fail)
         let status_icon = self
             .icons
-            .status_icon(report_template.status(art, dependent_data))
+            .status_icon(report_template.status(
+                art,
+                &placement,
+                shirt_color,
+                dependent_data,
+                thresholds,
+            ))
             .max_width(STATUS_ICON_WIDTH * scale);
 
         // Column 3 - The text for the score of the report's metric
-        let metric_text = report_template.metric_text(art, dependent_data);
+        let metric_text = report_template.metric_text(art, &placement, shirt_color, dependent_data);
         let metric = mtexts(&metric_text, scale);
 
         // Column 4 - The postfix after the matrix (either a % or nothing)
@@ -309,7 +517,13 @@ impl TShirtCheckerApp {
 
                     // Column 5 - The tool select button (painted separately)
                     strip.cell(|ui| {
-                        let status = report_template.status(art, dependent_data);
+                        let status = report_template.status(
+                            art,
+                            &placement,
+                            shirt_color,
+                            dependent_data,
+                            thresholds,
+                        );
                         self.paint_tool_button(changes, ui, scale, report_type, status);
                     });
                 });
@@ -354,25 +568,163 @@ impl TShirtCheckerApp {
         }
     }
 
-    // Paint all the t-hsirts in two rows
+    // Paint all the t-shirts in the color catalog.  Small catalogs get three big
+    // t-shirts a row, bigger catalogs get six small ones.
     //
     fn paint_tshirt_selection_panel(
         &self,
         changes: &mut ChangesToBeMade,
         ui: &mut egui::Ui,
         scale: f32,
+    ) {
+        let catalog_size = self.tshirt_image_storage.catalog().colors.len();
+        let per_row = if catalog_size <= 6 { 3 } else { 6 };
+        let width = BUTTON_WIDTH * scale * 3.0 / (per_row as f32);
+        let indices: Vec<usize> = (0..catalog_size).collect();
+        for row in indices.chunks(per_row) {
+            ui.horizontal(|ui| {
+                for index in row {
+                    let color = TShirtColors::Catalog(*index);
+                    self.paint_tshirt_select_button(changes, ui, width, color);
+                }
+            });
+        }
+        self.paint_custom_tshirt_colors(changes, ui, scale);
+        self.paint_garments(changes, ui, scale);
+        Self::paint_panel_separator(ui, scale);
+    }
+
+    // Paint the t-shirts for the user's custom colors, and the color picker used to
+    // add more.
+    //
+    fn paint_custom_tshirt_colors(
+        &self,
+        mut changes: &mut ChangesToBeMade,
+        ui: &mut egui::Ui,
+        scale: f32,
     ) {
         ui.horizontal(|ui| {
-            self.paint_tshirt_select_button(changes, ui, scale, TShirtColors::Red);
-            self.paint_tshirt_select_button(changes, ui, scale, TShirtColors::Green);
-            self.paint_tshirt_select_button(changes, ui, scale, TShirtColors::Blue);
+            for color in self.tshirt_image_storage.custom_colors() {
+                let width = BUTTON_WIDTH * scale / 2.0;
+                let response = self.paint_tshirt_select_button(
+                    changes,
+                    ui,
+                    width,
+                    TShirtColors::Custom(color),
+                );
+                response.context_menu(|ui| {
+                    if ui.button("Remove").clicked() {
+                        changes += Box::new(move |app: &mut Self| app.remove_custom_color(color));
+                        ui.close_menu();
+                    }
+                });
+            }
+
+            ui.menu_button("Custom...", |ui| {
+                let mut color = self.custom_color;
+                if egui::color_picker::color_picker_color32(
+                    ui,
+                    &mut color,
+                    egui::color_picker::Alpha::Opaque,
+                ) {
+                    changes += Box::new(move |app: &mut Self| {
+                        app.custom_color = color;
+                        app.custom_color_hex = shirt_color_to_hex(color);
+                    });
+                }
+                let mut hex = self.custom_color_hex.clone();
+                ui.horizontal(|ui| {
+                    ui.label("Hex");
+                    if ui.text_edit_singleline(&mut hex).changed() {
+                        changes += Box::new(move |app: &mut Self| {
+                            if let Some(color) = shirt_color_from_hex(&hex) {
+                                app.custom_color = color;
+                            }
+                            app.custom_color_hex.clone_from(&hex);
+                        });
+                    }
+                });
+                if ui.button("Use this color").clicked() {
+                    changes += Box::new(move |app: &mut Self| {
+                        app.tshirt_image_storage.add_custom_color(color);
+                        app.selected_tshirt = TShirtColors::Custom(color);
+                    });
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_text("Pick any color for the T-Shirt.");
+
+            ui.menu_button("Catalog", |ui| {
+                if ui.button("Load catalog...").clicked() {
+                    crate::async_tasks::load_shirt_catalog(
+                        ui.ctx(),
+                        &self.async_data_to_app_sender,
+                    );
+                    ui.close_menu();
+                }
+                if ui.button("Use default catalog").clicked() {
+                    changes += Box::new(|app: &mut Self| {
+                        app.set_shirt_catalog(ShirtColorCatalog::default())
+                    });
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_text("Load the shirt colors a vendor sells from a JSON file.");
         });
+    }
+
+    // Paint the imported garment mockups, the import button, and the calibration
+    // controls for the selected garment.
+    //
+    fn paint_garments(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui, scale: f32) {
         ui.horizontal(|ui| {
-            self.paint_tshirt_select_button(changes, ui, scale, TShirtColors::DRed);
-            self.paint_tshirt_select_button(changes, ui, scale, TShirtColors::DGreen);
-            self.paint_tshirt_select_button(changes, ui, scale, TShirtColors::DBlue);
+            for index in 0..self.tshirt_image_storage.garments().len() {
+                let width = BUTTON_WIDTH * scale / 2.0;
+                let response = self.paint_tshirt_select_button(
+                    changes,
+                    ui,
+                    width,
+                    TShirtColors::Garment(index),
+                );
+                response.context_menu(|ui| {
+                    if ui.button("Remove").clicked() {
+                        changes += Box::new(move |app: &mut Self| app.remove_garment(index));
+                        ui.close_menu();
+                    }
+                });
+            }
+
+            if ui
+                .button("Import garment...")
+                .on_hover_text("Import a mockup photo of a hoodie, tank top, tote bag, etc.")
+                .clicked()
+            {
+                crate::async_tasks::load_garment(ui.ctx(), &self.async_data_to_app_sender);
+            }
+
+            if let TShirtColors::Garment(index) = self.selected_tshirt {
+                let mut calibrating = self.calibrating;
+                if ui
+                    .toggle_value(&mut calibrating, "Calibrate")
+                    .on_hover_text(
+                        "Drag the corners of the print area to where it is on the garment.",
+                    )
+                    .changed()
+                {
+                    changes += Box::new(move |app: &mut Self| app.calibrating = calibrating);
+                }
+                if self.calibrating && ui.button("Reset").clicked() {
+                    let print_area = self.selected_print_area();
+                    changes += Box::new(move |app: &mut Self| {
+                        if let Some(garment) = app.tshirt_image_storage.garment_mut(index) {
+                            garment.calibration_mut().reset(print_area);
+                        }
+                    });
+                }
+            }
         });
-        Self::paint_panel_separator(ui, scale);
     }
 
     // Paint one t-shirt
@@ -381,25 +733,28 @@ impl TShirtCheckerApp {
         &self,
         mut changes: &mut ChangesToBeMade,
         ui: &mut egui::Ui,
-        scale: f32,
+        width: f32,
         color: TShirtColors,
-    ) {
-        let width = BUTTON_WIDTH * scale;
-        let image: &LoadedImage = self.tshirt_image_storage.tshirt_enum_to_image(color);
-        let egui_image = egui::Image::from_texture(image.texture_handle()).max_width(width);
+    ) -> egui::Response {
+        let image: &LoadedImage = self
+            .tshirt_image_storage
+            .tshirt_enum_to_image(color, self.selected_side);
+        let egui_image = egui::Image::from_texture(image.texture_handle(ui.ctx())).max_width(width);
         let is_selected = self.selected_tshirt == color;
-        if ui
+        let response = ui
             .add(egui::widgets::ImageButton::new(egui_image).selected(is_selected))
-            .clicked()
-        {
+            .on_hover_text(self.tshirt_image_storage.tshirt_enum_to_name(color));
+        if response.clicked() {
             // If the t-shirt button is clicked, schedule the t-shirt change
             changes += Box::new(move |app: &mut Self| {
                 app.selected_tshirt = color;
             });
         }
+        response
     }
 
-    // Paint the three potential pieces of art-work
+    // Paint the three potential pieces of art-work for the front, or the art for the
+    // back.
     //
     fn paint_artwork_selection_panel(
         &self,
@@ -408,14 +763,110 @@ impl TShirtCheckerApp {
         ctx: &egui::Context,
         scale: f32,
     ) {
-        ui.horizontal(|ui| {
-            self.paint_art_select_button(changes, ui, ctx, scale, ArtEnum::Artwork0);
-            self.paint_art_select_button(changes, ui, ctx, scale, ArtEnum::Artwork1);
-            self.paint_art_select_button(changes, ui, ctx, scale, ArtEnum::Artwork2);
+        ui.horizontal(|ui| match self.selected_side {
+            ShirtSide::Front => {
+                self.paint_art_select_button(changes, ui, ctx, scale, ArtEnum::Artwork0);
+                self.paint_art_select_button(changes, ui, ctx, scale, ArtEnum::Artwork1);
+                self.paint_art_select_button(changes, ui, ctx, scale, ArtEnum::Artwork2);
+            }
+            ShirtSide::Back => {
+                self.paint_art_select_button(changes, ui, ctx, scale, ArtEnum::BackArtwork);
+            }
         });
+        self.paint_placement_controls(changes, ui);
         Self::paint_panel_separator(ui, scale);
     }
 
+    // Move, scale and rotate the selected art.  "Place art" turns on the handles in
+    // the central panel and shows the placement in inches, so it can be typed in.
+    // Art can also be printed at the size its image file says it should be.
+    //
+    fn paint_placement_controls(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui) {
+        let art_id = self.displayed_art_id();
+        let print_area = self.selected_print_area();
+        let placement = self.selected_placement();
+        let mut edited = placement;
+
+        ui.horizontal(|ui| {
+            let mut placing_art = self.placing_art;
+            if ui
+                .toggle_value(&mut placing_art, "Place art")
+                .on_hover_text(
+                    "Drag the art to move it, drag a corner to scale it, or drag the round handle to rotate it.",
+                )
+                .changed()
+            {
+                changes += Box::new(move |app: &mut Self| app.placing_art = placing_art);
+            }
+            if ui
+                .button("Fit")
+                .on_hover_text("Scale the art to fill the print area.")
+                .clicked()
+            {
+                changes += Box::new(move |app: &mut Self| {
+                    app.art_storage.set_placement(art_id, print_area, None)
+                });
+            }
+
+            let art = self.get_selected_art();
+            let embedded_dpi = art.embedded_dpi();
+            let embedded_size_tip = match embedded_dpi {
+                Some(dpi) => format!("Print the art at {:.0} DPI, like its image file says.", dpi),
+                None => "The image file doesn't say what size to print it at.".to_string(),
+            };
+            if ui
+                .add_enabled(embedded_dpi.is_some(), egui::Button::new("Embedded size"))
+                .on_hover_text(embedded_size_tip.clone())
+                .on_disabled_hover_text(embedded_size_tip)
+                .clicked()
+            {
+                let placed = ArtPlacement::at_dpi(art.size(), embedded_dpi.unwrap(), print_area);
+                changes += Box::new(move |app: &mut Self| {
+                    app.art_storage
+                        .set_placement(art_id, print_area, Some(placed))
+                });
+            }
+        });
+
+        if let Some(warning) = placement.embedded_dpi_warning(self.get_selected_art()) {
+            ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", warning));
+        }
+
+        if self.placing_art {
+            fn inches(value: &mut f32) -> egui::DragValue<'_> {
+                egui::DragValue::new(value)
+                    .speed(0.05)
+                    .max_decimals(2)
+                    .suffix(" in")
+            }
+            ui.horizontal(|ui| {
+                ui.label("X");
+                ui.add(inches(&mut edited.center.x));
+                ui.label("Y");
+                ui.add(inches(&mut edited.center.y));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Width");
+                ui.add(inches(&mut edited.width).range(MIN_ART_WIDTH..=f32::MAX));
+                ui.label("Rotate");
+                ui.add(
+                    egui::DragValue::new(&mut edited.rotation)
+                        .speed(1.0)
+                        .max_decimals(1)
+                        .range(-180.0..=180.0)
+                        .suffix("°"),
+                );
+            });
+        }
+
+        if edited != placement {
+            changes += Box::new(move |app: &mut Self| {
+                app.art_storage
+                    .set_placement(art_id, print_area, Some(edited))
+            });
+        }
+    }
+
     //
     // Paint the button for one piece of artwork
     //
@@ -429,8 +880,8 @@ impl TShirtCheckerApp {
     ) {
         let width = BUTTON_WIDTH * scale;
         let image: &LoadedImage = self.art_storage.get_art(artwork);
-        let egui_image = egui::Image::from_texture(image.texture_handle()).max_width(width);
-        let is_selected = self.selected_art_id == artwork;
+        let egui_image = egui::Image::from_texture(image.texture_handle(ui.ctx())).max_width(width);
+        let is_selected = self.displayed_art_id() == artwork;
         if ui
             .add(egui::widgets::ImageButton::new(egui_image).selected(is_selected))
             .clicked()
@@ -446,12 +897,16 @@ impl TShirtCheckerApp {
                     ctx,
                     self.art_storage.get_art(artwork),
                     artwork,
+                    self.user_placement(artwork),
+                    &self.settings,
                     &self.async_data_to_app_sender,
                 );
             }
             // Schedule the artwork change after the paint is done
             changes += Box::new(move |app: &mut Self| {
-                app.selected_art_id = artwork;
+                if artwork.side() == ShirtSide::Front {
+                    app.selected_art_id = artwork;
+                }
                 app.selected_tool.reset();
             });
         }
@@ -467,7 +922,12 @@ impl TShirtCheckerApp {
             .clicked()
         {
             // Start an asyncronous load task
-            crate::async_tasks::do_load(ctx, self.selected_art_id, &self.async_data_to_app_sender);
+            crate::async_tasks::do_load(
+                ctx,
+                self.displayed_art_id(),
+                &self.settings,
+                &self.async_data_to_app_sender,
+            );
         }
     }
 
@@ -475,104 +935,849 @@ impl TShirtCheckerApp {
     //
     fn paint_partial_transparency_fix_button(
         &self,
+        mut changes: &mut ChangesToBeMade,
         ui: &mut egui::Ui,
-        ctx: &egui::Context,
         scale: f32,
     ) {
         let width = BUTTON_WIDTH * scale;
         if ui
             .add(self.icons.button(Icon::FixPT, width))
             .on_hover_text(
-                "Fix partial transparency problems by mapping all alpha values to 0 or 1.",
+                "Fix partial transparency problems by choosing which pixels get printed.",
             )
             .clicked()
         {
-            // Start the partial transparency fix asyncronously.
-            crate::async_tasks::partialt_fix(
+            // Open the fix window, with the partial transparency tool previewing it.
+            changes += Box::new(|app: &mut Self| {
+                app.fixing_alpha = !app.fixing_alpha;
+                app.selected_tool
+                    .set(ReportTypes::PartialTransparency, app.fixing_alpha);
+            });
+        }
+    }
+
+    // Open the halftone fix, the gentler fix for partial transparency.
+    //
+    fn paint_halftone_button(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui) {
+        let ready = self.get_selected_dependent_data().is_some();
+        if ui
+            .add_enabled(ready, egui::Button::new("Halftone..."))
+            .on_hover_text("Fix partial transparency problems by turning fades into dots.")
+            .clicked()
+        {
+            changes += Box::new(|app: &mut Self| app.halftoning = !app.halftoning);
+        }
+    }
+
+    // Crop the transparent margin off the art.  Only offered when there's a margin.
+    //
+    fn paint_crop_margins_button(&self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let has_margin = self
+            .get_selected_dependent_data()
+            .is_some_and(|dd| dd.margin_check().margin_percent() > 0);
+        if ui
+            .add_enabled(has_margin, egui::Button::new("Crop margins"))
+            .on_hover_text("Crop the transparent margin off the artwork.")
+            .clicked()
+        {
+            // Start the crop asyncronously.  Art the user placed stays where they put
+            // it.
+            let art_id = self.displayed_art_id();
+            let content = self.art_storage.get_content(art_id);
+            let art_size = self.get_selected_art().size();
+            crate::async_tasks::crop_margins_fix(
                 ctx,
                 self.get_selected_art(),
-                self.selected_art_id,
+                art_id,
+                self.user_placement(art_id)
+                    .map(|placement| placement.crop(art_size, content)),
+                &self.settings,
                 &self.async_data_to_app_sender,
             );
         }
     }
 
-    // A separator for the panels on the right hand side.
+    // Take the matte out of the art's soft edges.  Only offered when there's a fringe.
     //
-    fn paint_panel_separator(ui: &mut egui::Ui, scale: f32) {
-        ui.add_space(5.0 * scale);
-        ui.separator();
-        ui.add_space(5.0 * scale);
+    fn paint_defringe_button(&self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let matte = self
+            .get_selected_dependent_data()
+            .and_then(|dd| dd.fringe_check().matte());
+        if ui
+            .add_enabled(matte.is_some(), egui::Button::new("Defringe"))
+            .on_hover_text("Fix fringe problems by taking the matte color out of the soft edges, then fixing the partial transparency.")
+            .clicked()
+        {
+            // Start the defringe asyncronously.
+            crate::async_tasks::defringe_fix(
+                ctx,
+                self.get_selected_art(),
+                self.displayed_art_id(),
+                self.user_placement(self.displayed_art_id()),
+                matte.unwrap(),
+                &self.settings,
+                &self.async_data_to_app_sender,
+            );
+        }
     }
 
-    ////////////////////////////////////////////////////////////////////////////////////
-    //
-    // Paint the central panel (active t-shirt, art, and any tool output
+    // Thicken the lines the Thin Lines report flags.  Only offered when there are some.
     //
-    ////////////////////////////////////////////////////////////////////////////////////
+    fn paint_thin_line_fix_button(&self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let dd = self.get_selected_dependent_data();
+        let has_thin_lines = dd.is_some_and(|dd| dd.thin_line_percent() > 0);
+        if ui
+            .add_enabled(has_thin_lines, egui::Button::new("Thicken lines"))
+            .on_hover_text(
+                "Fix thin line problems by thickening them to the thinnest printable line.",
+            )
+            .clicked()
+        {
+            // Start the thin line fix asyncronously.
+            crate::async_tasks::thin_line_fix(
+                ctx,
+                self.get_selected_art(),
+                self.displayed_art_id(),
+                self.user_placement(self.displayed_art_id()),
+                dd.unwrap().thin_line_pixels(),
+                &self.settings,
+                &self.async_data_to_app_sender,
+            );
+        }
+    }
 
-    fn paint_central_panel(&self, changes: &mut ChangesToBeMade, ctx: &egui::Context) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let display_size = ui.available_size_before_wrap();
-            let (response, painter) =
-                ui.allocate_painter(display_size, egui::Sense::click_and_drag());
+    // Erase the specks the Specks report flags.  Only offered when there are some.
+    //
+    fn paint_speck_fix_button(&self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let dd = self.get_selected_dependent_data();
+        let dpi = self
+            .selected_placement()
+            .dpi(self.get_selected_art().size());
+        let has_specks = dd.is_some_and(|dd| dd.specks(dpi).next().is_some());
+        if ui
+            .add_enabled(has_specks, egui::Button::new("Remove specks"))
+            .on_hover_text("Fix speck problems by erasing the specks.")
+            .clicked()
+        {
+            // Start the speck fix asyncronously.
+            crate::async_tasks::speck_fix(
+                ctx,
+                self.get_selected_art(),
+                self.displayed_art_id(),
+                self.user_placement(self.displayed_art_id()),
+                dd.unwrap().speck_pixels(dpi),
+                &self.settings,
+                &self.async_data_to_app_sender,
+            );
+        }
+    }
 
-            let movement_happened =
-                self.handle_central_movement(changes, ui, response, display_size);
-            self.paint_tshirt(&painter, display_size);
-            self.paint_artwork(&painter, display_size);
+    // Remove the solid background art like JPEGs comes on.  The menu has the
+    // tolerance for how close to the background color a pixel has to be to go.
+    //
+    fn paint_background_fix_button(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui) {
+        let ready = self.get_selected_dependent_data().is_some();
+        let mut tolerance = self.settings.background_tolerance;
+        ui.add_enabled_ui(ready, |ui| {
+            ui.menu_button("Remove background", |ui| {
+                ui.add(egui::Slider::new(&mut tolerance, 0..=128).text("Tolerance"))
+                    .on_hover_text(
+                        "How far from the background color a pixel can be and still be removed.",
+                    );
+                if ui
+                    .button("Remove")
+                    .on_hover_text("Make the background around the artwork transparent.")
+                    .clicked()
+                {
+                    // Start the background removal asyncronously.
+                    crate::async_tasks::background_fix(
+                        ui.ctx(),
+                        self.get_selected_art(),
+                        self.displayed_art_id(),
+                        self.user_placement(self.displayed_art_id()),
+                        &self.settings,
+                        &self.async_data_to_app_sender,
+                    );
+                    ui.close_menu();
+                }
+            });
+        });
+        if tolerance != self.settings.background_tolerance {
+            let mut settings = self.settings.clone();
+            settings.background_tolerance = tolerance;
+            let ctx = ui.ctx().clone();
+            changes += Box::new(move |app: &mut Self| app.set_settings(settings.clone(), &ctx));
+        }
+    }
 
-            if self.selected_tool.is_active(ReportTypes::Dpi) {
-                self.paint_dpi_tool(changes, movement_happened);
-            }
-            if self.selected_tool.is_active(ReportTypes::AreaUsed) {
-                self.paint_area_used_tool(&painter, display_size);
+    // Knock the shirt color out of the art.  The menu previews the art on the shirt
+    // without the shirt colored parts, and how much the Bib Score drops.
+    //
+    fn paint_knockout_button(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui) {
+        let dd = self.get_selected_dependent_data();
+        let mut tolerance = self.settings.knockout_tolerance;
+        let mut preview = self.knockout_preview;
+        let shirt_color = self.selected_shirt_color();
+        ui.add_enabled_ui(dd.is_some(), |ui| {
+            let menu = ui.menu_button("Knock out shirt color", |ui| {
+                let dd = dd.unwrap();
+                ui.add(egui::Slider::new(&mut tolerance, 0..=128).text("Tolerance"))
+                    .on_hover_text(
+                        "How far from the shirt color a pixel can be and still be knocked out.",
+                    );
+                ui.checkbox(&mut preview, "Preview on the T-Shirt");
+
+                let art_size = self.get_selected_art().size();
+                let canvas_used = self.selected_placement().percent_of_print_area(
+                    art_size,
+                    art_size,
+                    dd.print_area(),
+                );
+                let bib_score =
+                    |opaque_percent: u32| (canvas_used * opaque_percent as f32 / 100.0) as u32;
+                let knocked_out = dd.knockout_preview().preview(shirt_color, tolerance);
+                ui.label(format!(
+                    "Bib Score {}% -> {}%",
+                    bib_score(dd.bib_opaque_percent()),
+                    bib_score(knocked_out.opaque_percent)
+                ));
+                if ui
+                    .button("Apply")
+                    .on_hover_text(
+                        "Make the parts of the artwork that match the T-Shirt transparent.",
+                    )
+                    .clicked()
+                {
+                    let mut settings = self.settings.clone();
+                    settings.knockout_tolerance = tolerance;
+                    // Start the knock out asyncronously.
+                    crate::async_tasks::knockout_fix(
+                        ui.ctx(),
+                        self.get_selected_art(),
+                        self.displayed_art_id(),
+                        self.user_placement(self.displayed_art_id()),
+                        shirt_color,
+                        &settings,
+                        &self.async_data_to_app_sender,
+                    );
+                    preview = false;
+                    ui.close_menu();
+                }
+            });
+            // The preview only lasts as long as the menu's open.
+            if menu.inner.is_none() {
+                preview = false;
             }
         });
+        if preview != self.knockout_preview {
+            changes += Box::new(move |app: &mut Self| app.knockout_preview = preview);
+        }
+        if tolerance != self.settings.knockout_tolerance {
+            let mut settings = self.settings.clone();
+            settings.knockout_tolerance = tolerance;
+            let ctx = ui.ctx().clone();
+            changes += Box::new(move |app: &mut Self| app.set_settings(settings.clone(), &ctx));
+        }
     }
 
-    fn handle_central_movement(
-        &self,
-        changes: &mut ChangesToBeMade,
-        ui: &egui::Ui,
-        response: egui::Response,
-        display_size: egui::Vec2,
-    ) -> bool {
-        let dragged = self.handle_central_movement_drag(changes, &response, display_size);
-        let zoomed = self.handle_central_movement_zoom(changes, ui, &response);
-
-        dragged || zoomed
+    // Save the selected artwork's reports, either as JSON data for an order system or
+    // as an HTML file that can be sent to a customer.
+    //
+    fn paint_export_button(&self, ui: &mut egui::Ui, ctx: &egui::Context, scale: f32) {
+        let width = BUTTON_WIDTH * scale;
+        let reports_ready = self.are_all_reports_ready();
+        let icon = self
+            .icons
+            .image(Icon::Export, width)
+            .bg_fill(egui::Color32::WHITE);
+        ui.add_enabled_ui(reports_ready, |ui| {
+            ui.menu_image_button(icon, |ui| {
+                if ui.button("Report data (JSON)").clicked() {
+                    let report = self.selected_artwork_report();
+                    crate::async_tasks::save_report(
+                        ctx,
+                        report.to_json(),
+                        &self.async_data_to_app_sender,
+                    );
+                    ui.close_menu();
+                }
+                if ui.button("Shareable report (HTML)").clicked() {
+                    let report = HtmlReport::new(
+                        self.selected_artwork_report(),
+                        self.get_selected_art(),
+                        self.get_selected_dependent_data().unwrap(),
+                        &self.report_templates,
+                        self.tshirt_image_storage
+                            .tshirt_enum_to_image(self.selected_tshirt, self.selected_side),
+                        self.print_area_on_shirt(),
+                    );
+                    crate::async_tasks::save_html_report(
+                        ctx,
+                        report,
+                        &self.async_data_to_app_sender,
+                    );
+                    ui.close_menu();
+                }
+                if ui
+                    .button("Underbase mask (PNG)")
+                    .on_hover_text(
+                        "The white underbase for direct to garment printing on dark shirts.",
+                    )
+                    .clicked()
+                {
+                    let dependent_data = self.get_selected_dependent_data().unwrap();
+                    crate::async_tasks::save_png(
+                        ctx,
+                        dependent_data.underbase_preview().mask().clone(),
+                        "underbase_mask",
+                        &self.async_data_to_app_sender,
+                    );
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_text("Export the selected artwork's reports.");
+        });
     }
 
-    fn handle_central_movement_drag(
-        &self,
-        mut changes: &mut ChangesToBeMade,
-        response: &egui::Response,
-        display_size: egui::Vec2,
-    ) -> bool {
-        if let Some(pointer_pos) = response.interact_pointer_pos() {
-            let mouse_down_pos = vector!(pointer_pos[0], pointer_pos[1], 1.0);
-            let tshirt_to_display = tshirt_to_display(self.central_viewport(display_size));
-            changes += Box::new(move |app: &mut Self| {
-                app.move_state
-                    .event_mouse_down_movement(mouse_down_pos, tshirt_to_display);
-            });
-            true
-        } else {
-            changes += Box::new(move |app: &mut Self| {
-                app.move_state.event_mouse_released();
+    // The report settings window.  Edits are made to a copy of the settings, and
+    // the copy replaces the app's settings once the paint is done.
+    //
+    fn paint_settings_window(&self, mut changes: &mut ChangesToBeMade, ctx: &egui::Context) {
+        if !self.settings_open {
+            return;
+        }
+        let mut open = true;
+        let mut settings = self.settings.clone();
+        egui::Window::new("Report Thresholds")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("report_thresholds")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Report");
+                        ui.strong("Pass at");
+                        ui.strong("Warn at");
+                        ui.end_row();
+                        for report_type in ReportTypes::ALL {
+                            let template =
+                                self.report_templates.report_type_to_template(report_type);
+                            let postfix = template.postfix_string();
+                            let max = if template.display_percent { 100 } else { 2400 };
+                            let thresholds = settings.thresholds_mut(report_type);
+                            let direction = if thresholds.higher_is_better {
+                                "or more"
+                            } else {
+                                "or less"
+                            };
+                            ui.label(template.label.replace('\n', " "));
+                            ui.add(
+                                egui::DragValue::new(&mut thresholds.pass)
+                                    .range(0..=max)
+                                    .suffix(&postfix),
+                            );
+                            let warn_range = thresholds.warn_range(max);
+                            thresholds.warn = thresholds
+                                .warn
+                                .clamp(*warn_range.start(), *warn_range.end());
+                            ui.add(
+                                egui::DragValue::new(&mut thresholds.warn)
+                                    .range(warn_range)
+                                    .suffix(&postfix),
+                            );
+                            ui.label(direction);
+                            ui.end_row();
+                        }
+                    });
+                ui.separator();
+                egui::ComboBox::from_label("Thinnest printable line")
+                    .selected_text(format!("1/{} inch", settings.thin_line_fraction))
+                    .show_ui(ui, |ui| {
+                        for fraction in THIN_LINE_FRACTIONS {
+                            ui.selectable_value(
+                                &mut settings.thin_line_fraction,
+                                fraction,
+                                format!("1/{} inch", fraction),
+                            );
+                        }
+                    });
+                let speck_side = (1.0 / settings.max_speck_area.max(f32::EPSILON)).sqrt();
+                egui::ComboBox::from_label("Smallest printable area")
+                    .selected_text(format!("1/{:.0} inch square", speck_side))
+                    .show_ui(ui, |ui| {
+                        for fraction in SPECK_FRACTIONS {
+                            ui.selectable_value(
+                                &mut settings.max_speck_area,
+                                1.0 / (fraction * fraction) as f32,
+                                format!("1/{} inch square", fraction),
+                            );
+                        }
+                    });
+                ui.separator();
+                if ui.button("Reset to defaults").clicked() {
+                    settings = ReportSettings::default();
+                }
             });
-            false
+
+        if settings != self.settings {
+            let ctx = ctx.clone();
+            changes += Box::new(move |app: &mut Self| app.set_settings(settings.clone(), &ctx));
+        }
+        if !open {
+            changes += Box::new(|app: &mut Self| app.settings_open = false);
         }
     }
 
-    fn handle_central_movement_zoom(
-        &self,
-        mut changes: &mut ChangesToBeMade,
-        ui: &egui::Ui,
-        response: &egui::Response,
-    ) -> bool {
-        const ZOOM_RATE: f32 = 200.0;
+    // Choose which partially transparent pixels are printed.  The partial transparency
+    // tool blinks between the problems and the art as the fix would leave it, so the
+    // threshold and dithering can be tried out before they're applied.
+    //
+    fn paint_alpha_fix_window(&self, mut changes: &mut ChangesToBeMade, ctx: &egui::Context) {
+        if !self.fixing_alpha {
+            return;
+        }
+        let ready = self.get_selected_dependent_data().is_some();
+        let mut open = true;
+        let mut apply = false;
+        let mut alpha_fix = self.settings.alpha_fix;
+        egui::Window::new("Partial Transparency Fix")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut alpha_fix.threshold, 1..=100)
+                        .suffix("%")
+                        .text("Keep pixels at least this opaque"),
+                );
+                ui.checkbox(&mut alpha_fix.dither, "Dither")
+                    .on_hover_text("Turn fades into speckles instead of cutting them off.");
+                ui.label("The art blinks between the problems and the fixed art.");
+                ui.separator();
+                apply = ui
+                    .add_enabled(ready, egui::Button::new("Apply"))
+                    .on_hover_text("Replace the art with the fixed art.")
+                    .clicked();
+            });
+
+        if alpha_fix != self.settings.alpha_fix {
+            let mut settings = self.settings.clone();
+            settings.alpha_fix = alpha_fix;
+            let ctx = ctx.clone();
+            changes += Box::new(move |app: &mut Self| app.set_settings(settings.clone(), &ctx));
+        }
+        if apply {
+            let mut settings = self.settings.clone();
+            settings.alpha_fix = alpha_fix;
+            // Start the partial transparency fix asyncronously.
+            crate::async_tasks::partialt_fix(
+                ctx,
+                self.get_selected_art(),
+                self.displayed_art_id(),
+                self.user_placement(self.displayed_art_id()),
+                &settings,
+                &self.async_data_to_app_sender,
+            );
+        }
+        if !open || apply {
+            changes += Box::new(|app: &mut Self| {
+                app.fixing_alpha = false;
+                app.selected_tool.reset();
+            });
+        }
+    }
+
+    // Choose the halftone dots and compare the art before and after halftoning.  The
+    // preview is a close up of the art's worst partial transparency, with the dots
+    // sized for the resolution the art is printed at.
+    //
+    fn paint_halftone_window(&self, mut changes: &mut ChangesToBeMade, ctx: &egui::Context) {
+        if !self.halftoning {
+            return;
+        }
+        let Some(dd) = self.get_selected_dependent_data() else {
+            return;
+        };
+        let art = self.get_selected_art();
+        let dpi = self.selected_placement().dpi(art.size());
+        let mut open = true;
+        let mut apply = false;
+        let mut halftone = self.settings.halftone;
+        egui::Window::new("Halftone Fix")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("halftone_settings").show(ui, |ui| {
+                    ui.label("Dot shape");
+                    egui::ComboBox::from_id_source("dot_shape")
+                        .selected_text(halftone.shape.name())
+                        .show_ui(ui, |ui| {
+                            for shape in DotShape::ALL {
+                                ui.selectable_value(&mut halftone.shape, shape, shape.name());
+                            }
+                        });
+                    ui.end_row();
+                    ui.label("Lines per inch");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut halftone.lpi).range(10..=150));
+                        if ui
+                            .small_button("Suggest")
+                            .on_hover_text("Pick a line screen for the printed resolution.")
+                            .clicked()
+                        {
+                            halftone.lpi = HalftoneSettings::suggested_lpi(dpi);
+                        }
+                    });
+                    ui.end_row();
+                    ui.label("Angle");
+                    ui.add(
+                        egui::DragValue::new(&mut halftone.angle)
+                            .range(0.0..=90.0)
+                            .speed(0.5)
+                            .suffix("°"),
+                    );
+                    ui.end_row();
+                });
+                ui.label(format!(
+                    "Printed at {:.0} DPI, each dot is {:.1} pixels across.",
+                    dpi,
+                    halftone.cell_pixels(dpi)
+                ));
+                ui.separator();
+
+                let preview = dd.halftone_preview();
+                let after = preview.after(dpi, &halftone);
+                ui.horizontal(|ui| {
+                    for (label, image) in [("Before", preview.before()), ("After", &after)] {
+                        ui.vertical(|ui| {
+                            ui.label(label);
+                            let texture = image.texture_handle(ui.ctx());
+                            ui.add(
+                                egui::Image::from_texture(texture)
+                                    .fit_to_exact_size(image.size() * 1.5)
+                                    .bg_fill(self.selected_shirt_color()),
+                            );
+                        });
+                    }
+                });
+                ui.separator();
+                apply = ui
+                    .button("Apply")
+                    .on_hover_text("Replace the partial transparency with halftone dots.")
+                    .clicked();
+            });
+
+        if halftone != self.settings.halftone {
+            let mut settings = self.settings.clone();
+            settings.halftone = halftone;
+            let ctx = ctx.clone();
+            changes += Box::new(move |app: &mut Self| app.set_settings(settings.clone(), &ctx));
+        }
+        if apply {
+            let mut settings = self.settings.clone();
+            settings.halftone = halftone;
+            crate::async_tasks::halftone_fix(
+                ctx,
+                art,
+                self.displayed_art_id(),
+                self.user_placement(self.displayed_art_id()),
+                dpi,
+                &settings,
+                &self.async_data_to_app_sender,
+            );
+        }
+        if !open || apply {
+            changes += Box::new(|app: &mut Self| app.halftoning = false);
+        }
+    }
+
+    // Compare every piece of art on every shirt.  Each cell is a thumbnail of the art
+    // on the shirt with the scores for the reports that depend on the shirt color.
+    // The best shirt for each piece of art is outlined, and clicking a cell (or Use)
+    // shows that art on that shirt.
+    //
+    fn paint_comparison_window(&self, mut changes: &mut ChangesToBeMade, ctx: &egui::Context) {
+        if !self.comparing {
+            return;
+        }
+        let storage = &self.tshirt_image_storage;
+        let shirts: Vec<(TShirtColors, egui::Color32)> = storage
+            .all_tshirts()
+            .into_iter()
+            .map(|shirt| (shirt, storage.shirt_color(shirt)))
+            .collect();
+        let mut open = true;
+        egui::Window::new("Compare Shirts")
+            .open(&mut open)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::both().show(ui, |ui| {
+                    egui::Grid::new("shirt_comparison").show(ui, |ui| {
+                        for art_id in ArtEnum::ALL {
+                            self.paint_comparison_row(changes, ui, art_id, &shirts);
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        if !open {
+            changes += Box::new(|app: &mut Self| app.comparing = false);
+        }
+    }
+
+    // One piece of art on every shirt, with the best shirt suggested on the left.
+    //
+    fn paint_comparison_row(
+        &self,
+        mut changes: &mut ChangesToBeMade,
+        ui: &mut egui::Ui,
+        art_id: ArtEnum,
+        shirts: &[(TShirtColors, egui::Color32)],
+    ) {
+        let Some(dependent_data) = self.art_storage.get_dependent_data(art_id) else {
+            ui.strong(art_id.name());
+            ui.spinner();
+            return;
+        };
+        let art = self.art_storage.get_art(art_id);
+        let print_area = self.settings.print_area_for(art_id.side());
+        let scores = score_shirts(
+            art,
+            &self.art_storage.get_placement(art_id, print_area),
+            dependent_data,
+            &self.report_templates,
+            &self.settings,
+            shirts,
+        );
+        let best = best_shirt(&scores, &self.settings);
+
+        ui.vertical(|ui| {
+            ui.strong(art_id.name());
+            if let Some(shirt) = best {
+                ui.label(format!(
+                    "Best: {}",
+                    self.tshirt_image_storage.tshirt_enum_to_name(shirt)
+                ));
+                if ui.button("Use").clicked() {
+                    let ctx = ui.ctx().clone();
+                    changes +=
+                        Box::new(move |app: &mut Self| app.show_art_on_shirt(art_id, shirt, &ctx));
+                }
+            }
+        });
+        for score in &scores {
+            self.paint_comparison_cell(changes, ui, art_id, score, best == Some(score.shirt));
+        }
+    }
+
+    fn paint_comparison_cell(
+        &self,
+        mut changes: &mut ChangesToBeMade,
+        ui: &mut egui::Ui,
+        art_id: ArtEnum,
+        score: &ShirtScore,
+        is_best: bool,
+    ) {
+        let shirt = score.shirt;
+        let side = art_id.side();
+        let shirt_image = self.tshirt_image_storage.tshirt_enum_to_image(shirt, side);
+        let shirt_size = shirt_image.size();
+        let size = egui::vec2(
+            THUMBNAIL_WIDTH,
+            THUMBNAIL_WIDTH * shirt_size.y / shirt_size.x,
+        );
+
+        ui.vertical(|ui| {
+            let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+            let painter = ui.painter_at(rect);
+            painter.image(
+                shirt_image.id(ui.ctx()),
+                rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+
+            // The print area is in t-shirt space, where the shirt is the unit square.
+            let print_area = self.settings.print_area_for(side);
+            let on_shirt = self.tshirt_image_storage.print_area_rect(shirt, print_area);
+            let on_display = egui::Rect::from_min_max(
+                rect.lerp_inside(on_shirt.min.to_vec2()),
+                rect.lerp_inside(on_shirt.max.to_vec2()),
+            );
+            let art = self.art_storage.get_art(art_id);
+            let placement = self.art_storage.get_placement(art_id, print_area);
+            let art_to_display =
+                art_space_to_rect(print_area, on_display) * placement.art_to_art_space(art.size());
+            paint_art_quad(&painter, art.id(ui.ctx()), art_to_display);
+            if is_best {
+                painter.rect_stroke(rect, 4.0, egui::Stroke::new(3.0, egui::Color32::GREEN));
+            }
+
+            let name = self.tshirt_image_storage.tshirt_enum_to_name(shirt);
+            if response.on_hover_text(name).clicked() {
+                let ctx = ui.ctx().clone();
+                changes +=
+                    Box::new(move |app: &mut Self| app.show_art_on_shirt(art_id, shirt, &ctx));
+            }
+
+            for report in &score.reports {
+                let template = self.report_templates.report_type_to_template(report.report);
+                let metric = match report.metric {
+                    Some(n) => format!("{}{}", n, template.postfix_string()),
+                    None => "???".to_string(),
+                };
+                ui.horizontal(|ui| {
+                    ui.add(self.icons.status_icon(report.status).max_width(14.0));
+                    ui.label(metric);
+                })
+                .response
+                .on_hover_text(template.label.replace('\n', " "));
+            }
+        });
+    }
+
+    // A separator for the panels on the right hand side.
+    //
+    fn paint_panel_separator(ui: &mut egui::Ui, scale: f32) {
+        ui.add_space(5.0 * scale);
+        ui.separator();
+        ui.add_space(5.0 * scale);
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////
+    //
+    // Paint the central panel (active t-shirt, art, and any tool output
+    //
+    ////////////////////////////////////////////////////////////////////////////////////
+
+    fn paint_central_panel(&self, changes: &mut ChangesToBeMade, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let display_size = ui.available_size_before_wrap();
+            let (response, painter) =
+                ui.allocate_painter(display_size, egui::Sense::click_and_drag());
+
+            let movement_happened =
+                self.handle_central_movement(changes, ui, response, display_size);
+            self.paint_tshirt(&painter, display_size);
+            self.paint_artwork(&painter, display_size);
+
+            if self.selected_tool.is_active(ReportTypes::Dpi) {
+                self.paint_dpi_tool(changes, movement_happened);
+            }
+            if self.selected_tool.is_active(ReportTypes::AreaUsed) {
+                self.paint_area_used_tool(&painter, display_size);
+            }
+            if self.selected_tool.is_active(ReportTypes::ColorCount) {
+                self.paint_color_count_tool(ui, &painter);
+            }
+            if self.selected_tool.is_active(ReportTypes::Background) {
+                self.paint_background_tool(&painter, display_size);
+            }
+            if self.selected_tool.is_active(ReportTypes::Margins) {
+                self.paint_margins_tool(&painter, display_size);
+            }
+            if self.selected_tool.is_active(ReportTypes::Specks) {
+                self.paint_specks_tool(&painter, display_size);
+            }
+            if self.is_calibrating() {
+                self.paint_calibration_tool(changes, ui, &painter, display_size);
+            }
+            if self.placing_art {
+                self.paint_placement_tool(changes, ui, &painter, display_size);
+            }
+            self.paint_side_select(changes, ui);
+        });
+    }
+
+    // Front/ back toggle, painted over the top left corner of the central panel
+    //
+    fn paint_side_select(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui) {
+        let mut side = self.selected_side;
+        let corner = egui::Rect::from_min_size(ui.min_rect().min, egui::vec2(520.0, 30.0));
+        ui.allocate_ui_at_rect(corner, |ui| {
+            ui.horizontal(|ui| {
+                for choice in ShirtSide::ALL {
+                    ui.selectable_value(&mut side, choice, choice.name());
+                }
+                self.paint_dtg_preview_select(changes, ui);
+            });
+        });
+        if side != self.selected_side {
+            let ctx = ui.ctx().clone();
+            changes += Box::new(move |app: &mut Self| app.set_side(side, &ctx));
+        }
+    }
+
+    // Dark shirts can preview the art as a direct to garment print, with and without
+    // the white underbase.
+    //
+    fn paint_dtg_preview_select(&self, mut changes: &mut ChangesToBeMade, ui: &mut egui::Ui) {
+        if !is_dark(self.selected_shirt_color()) {
+            return;
+        }
+        ui.separator();
+        let mut dtg_preview = self.dtg_preview;
+        ui.selectable_value(&mut dtg_preview, DtgPreview::Off, "Art")
+            .on_hover_text("Show the art as it is.");
+        ui.selectable_value(
+            &mut dtg_preview,
+            DtgPreview::NoUnderbase,
+            "DTG, no underbase",
+        )
+        .on_hover_text("Simulate a direct to garment print without a white underbase.");
+        ui.selectable_value(
+            &mut dtg_preview,
+            DtgPreview::Underbase,
+            "DTG with underbase",
+        )
+        .on_hover_text("Simulate a direct to garment print on a white underbase.");
+        if dtg_preview != self.dtg_preview {
+            changes += Box::new(move |app: &mut Self| app.dtg_preview = dtg_preview);
+        }
+    }
+
+    fn handle_central_movement(
+        &self,
+        changes: &mut ChangesToBeMade,
+        ui: &egui::Ui,
+        response: egui::Response,
+        display_size: egui::Vec2,
+    ) -> bool {
+        let dragged = self.handle_central_movement_drag(changes, &response, display_size);
+        let zoomed = self.handle_central_movement_zoom(changes, ui, &response);
+
+        dragged || zoomed
+    }
+
+    fn handle_central_movement_drag(
+        &self,
+        mut changes: &mut ChangesToBeMade,
+        response: &egui::Response,
+        display_size: egui::Vec2,
+    ) -> bool {
+        if let Some(pointer_pos) = response.interact_pointer_pos() {
+            let mouse_down_pos = vector!(pointer_pos[0], pointer_pos[1], 1.0);
+            let tshirt_to_display = tshirt_to_display(self.central_viewport(display_size));
+            changes += Box::new(move |app: &mut Self| {
+                app.move_state
+                    .event_mouse_down_movement(mouse_down_pos, tshirt_to_display);
+            });
+            true
+        } else {
+            changes += Box::new(move |app: &mut Self| {
+                app.move_state.event_mouse_released();
+            });
+            false
+        }
+    }
+
+    fn handle_central_movement_zoom(
+        &self,
+        mut changes: &mut ChangesToBeMade,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> bool {
+        const ZOOM_RATE: f32 = 200.0;
         if response.hovered() {
             let zoom_delta_0 = 1.0 + ui.ctx().input(|i| i.smooth_scroll_delta)[1] / ZOOM_RATE;
             let zoom_delta_1 = ui.ctx().input(|i| i.zoom_delta());
@@ -596,10 +1801,10 @@ impl TShirtCheckerApp {
 
         let tshirt_art = self
             .tshirt_image_storage
-            .tshirt_enum_to_image(self.selected_tshirt);
+            .tshirt_enum_to_image(self.selected_tshirt, self.selected_side);
 
         painter.image(
-            tshirt_art.id(),
+            tshirt_art.id(painter.ctx()),
             egui::Rect::from_min_max(s0, s1),
             egui::Rect::from_min_max(uv0, uv1),
             egui::Color32::WHITE,
@@ -611,11 +1816,7 @@ impl TShirtCheckerApp {
         let art_space_to_display = tshirt_to_display * self.art_space_to_shirt_matrix();
         let art_to_display = art_space_to_display * self.art_to_art_space_matrix();
 
-        let a0 = v3_to_egui(art_to_display * dvector![0.0, 0.0, 1.0]);
-        let a1 = v3_to_egui(art_to_display * dvector![1.0, 1.0, 1.0]);
-        let uv0 = egui::Pos2 { x: 0.0, y: 0.0 };
-        let uv1 = egui::Pos2 { x: 1.0, y: 1.0 };
-
+        let ctx = painter.ctx();
         let cycle = self.selected_tool.get_cycles();
         let texture_to_display = if self
             .selected_tool
@@ -623,34 +1824,67 @@ impl TShirtCheckerApp {
         {
             let dependent_data = self.get_selected_dependent_data().unwrap();
             match cycle % 2 {
-                0 => dependent_data.partial_transparency_problems().id(),
-                _ => dependent_data.partial_transparency_fixed().id(),
+                0 => dependent_data.partial_transparency_problems().id(ctx),
+                _ => dependent_data
+                    .partial_transparency_fixed(&self.settings.alpha_fix)
+                    .id(ctx),
             }
         } else if self.selected_tool.is_active(ReportTypes::Dpi) {
             let dependent_data = self.get_selected_dependent_data().unwrap();
-            dependent_data.partial_transparency_fixed().id()
+            dependent_data
+                .partial_transparency_fixed(&self.settings.alpha_fix)
+                .id(ctx)
         } else if self.selected_tool.is_active(ReportTypes::ThinLines) {
             let dependent_data = self.get_selected_dependent_data().unwrap();
             match cycle % 2 {
-                0 => dependent_data.thin_line_problems().id(),
-                _ => self.get_selected_art().id(),
+                0 => dependent_data.thin_line_problems().id(ctx),
+                _ => self.get_selected_art().id(ctx),
             }
         } else if self.selected_tool.is_active(ReportTypes::Bib) {
             let dependent_data = self.get_selected_dependent_data().unwrap();
             match (cycle / 2) % 2 {
-                0 => dependent_data.bib_opaque_mask().id(),
-                _ => self.get_selected_art().id(),
+                0 => dependent_data.bib_opaque_mask().id(ctx),
+                _ => self.get_selected_art().id(ctx),
+            }
+        } else if self.selected_tool.is_active(ReportTypes::Fringe) {
+            let dependent_data = self.get_selected_dependent_data().unwrap();
+            match cycle % 2 {
+                0 => dependent_data.fringe_check().flagged().id(ctx),
+                _ => self.get_selected_art().id(ctx),
+            }
+        } else if self.selected_tool.is_active(ReportTypes::Background) {
+            let dependent_data = self.get_selected_dependent_data().unwrap();
+            match cycle % 2 {
+                0 => dependent_data.background_check().flagged().id(ctx),
+                _ => self.get_selected_art().id(ctx),
+            }
+        } else if self.selected_tool.is_active(ReportTypes::LowContrast) {
+            let dependent_data = self.get_selected_dependent_data().unwrap();
+            match cycle % 2 {
+                0 => dependent_data
+                    .shirt_contrast()
+                    .low_contrast_problems(self.selected_shirt_color())
+                    .id(ctx),
+                _ => self.get_selected_art().id(ctx),
             }
+        } else if let Some(ink) = self.highlighted_ink() {
+            let dependent_data = self.get_selected_dependent_data().unwrap();
+            dependent_data.ink_palette().highlight(ink).id(ctx)
+        } else if self.knockout_preview && self.get_selected_dependent_data().is_some() {
+            let dependent_data = self.get_selected_dependent_data().unwrap();
+            let tolerance = self.settings.knockout_tolerance;
+            let knockout = dependent_data.knockout_preview();
+            knockout
+                .preview(self.selected_shirt_color(), tolerance)
+                .image
+                .id(ctx)
+        } else if let Some(preview) = self.dtg_preview_image() {
+            preview.id(ctx)
         } else {
-            self.get_selected_art().id()
+            self.get_selected_art().id(ctx)
         };
 
-        painter.image(
-            texture_to_display,
-            egui::Rect::from_min_max(a0, a1),
-            egui::Rect::from_min_max(uv0, uv1),
-            egui::Color32::WHITE,
-        );
+        paint_art_quad(painter, texture_to_display, art_to_display);
     }
 
     fn paint_dpi_tool(&self, mut changes: &mut ChangesToBeMade, movement_happened: bool) {
@@ -680,11 +1914,12 @@ impl TShirtCheckerApp {
         let tshirt_to_display = tshirt_to_display(self.central_viewport(display_size));
         let art_space_to_display = tshirt_to_display * self.art_space_to_shirt_matrix();
 
+        let print_size = self.selected_print_area().size_in_inches();
         let art_space_border = vec![
             v3_to_egui(art_space_to_display * dvector![0.0, 0.0, 1.0]),
-            v3_to_egui(art_space_to_display * dvector![11.0, 0.0, 1.0]),
-            v3_to_egui(art_space_to_display * dvector![11.0, 14.0, 1.0]),
-            v3_to_egui(art_space_to_display * dvector![0.0, 14.0, 1.0]),
+            v3_to_egui(art_space_to_display * dvector![print_size.x, 0.0, 1.0]),
+            v3_to_egui(art_space_to_display * dvector![print_size.x, print_size.y, 1.0]),
+            v3_to_egui(art_space_to_display * dvector![0.0, print_size.y, 1.0]),
             v3_to_egui(art_space_to_display * dvector![0.0, 0.0, 1.0]),
         ];
 
@@ -711,12 +1946,310 @@ impl TShirtCheckerApp {
         ));
     }
 
+    // Background tool.  The art flashes with its background painted over, and a
+    // marching dashed line goes around the art, where the background stops.
+    //
+    fn paint_background_tool(&self, painter: &egui::Painter, display_size: egui::Vec2) {
+        if let Some(bounds) = self
+            .get_selected_dependent_data()
+            .and_then(|dd| dd.background_check().art_bounds())
+        {
+            self.paint_marching_ants(painter, display_size, bounds);
+        }
+    }
+
+    // Margins tool.  The edge of the art's content is outlined, so the transparent
+    // margin around it shows.
+    //
+    fn paint_margins_tool(&self, painter: &egui::Painter, display_size: egui::Vec2) {
+        if let Some(dd) = self.get_selected_dependent_data() {
+            self.paint_marching_ants(painter, display_size, dd.margin_check().content());
+        }
+    }
+
+    // An animated dashed outline around a rectangle in the art, in pixels of the art.
+    //
+    fn paint_marching_ants(
+        &self,
+        painter: &egui::Painter,
+        display_size: egui::Vec2,
+        bounds: egui::Rect,
+    ) {
+        let tshirt_to_display = tshirt_to_display(self.central_viewport(display_size));
+        let art_space_to_display = tshirt_to_display * self.art_space_to_shirt_matrix();
+        let art_to_display = art_space_to_display * self.art_to_art_space_matrix();
+        let art_size = self.get_selected_art().size();
+        let to_display = |pos: egui::Pos2| {
+            v3_to_egui(art_to_display * dvector![pos.x / art_size.x, pos.y / art_size.y, 1.0])
+        };
+        let outline: Vec<egui::Pos2> = [
+            bounds.left_top(),
+            bounds.right_top(),
+            bounds.right_bottom(),
+            bounds.left_bottom(),
+            bounds.left_top(),
+        ]
+        .into_iter()
+        .map(to_display)
+        .collect();
+
+        const DASH_LENGTH: f32 = 10.0;
+        let dash_offset = (self.selected_tool.get_cycles() % 2) as f32 * DASH_LENGTH;
+        for (width, color) in [(4.0, egui::Color32::BLACK), (2.0, egui::Color32::WHITE)] {
+            painter.add(egui::Shape::dashed_line_with_offset(
+                &outline,
+                egui::Stroke::new(width, color),
+                &[DASH_LENGTH],
+                &[DASH_LENGTH],
+                dash_offset,
+            ));
+        }
+    }
+
+    // Specks tool.  Every speck is circled, and the circles pulse so the smallest
+    // specks are easy to spot.
+    //
+    fn paint_specks_tool(&self, painter: &egui::Painter, display_size: egui::Vec2) {
+        const MIN_RADIUS: f32 = 6.0;
+        const PULSE: f32 = 4.0;
+        let Some(dependent_data) = self.get_selected_dependent_data() else {
+            return;
+        };
+        let tshirt_to_display = tshirt_to_display(self.central_viewport(display_size));
+        let art_space_to_display = tshirt_to_display * self.art_space_to_shirt_matrix();
+        let art_to_display = art_space_to_display * self.art_to_art_space_matrix();
+        let art_size = self.get_selected_art().size();
+        let to_display = |pos: egui::Pos2| {
+            v3_to_egui(art_to_display * dvector![pos.x / art_size.x, pos.y / art_size.y, 1.0])
+        };
+
+        let pulse = (self.selected_tool.get_cycles() % 2) as f32 * PULSE;
+        let dpi = self.selected_placement().dpi(art_size);
+        for speck in dependent_data.specks(dpi) {
+            let center = to_display(speck.center);
+            let edge = to_display(speck.center + egui::vec2(speck.radius, 0.0));
+            let radius = (edge - center).length().max(MIN_RADIUS) + pulse;
+            painter.circle_stroke(center, radius, egui::Stroke::new(3.0, egui::Color32::BLACK));
+            painter.circle_stroke(
+                center,
+                radius,
+                egui::Stroke::new(1.5, egui::Color32::YELLOW),
+            );
+        }
+    }
+
+    // Color count tool.  Swatches for every ink are painted under the front/ back
+    // toggle, and the art cycles through highlighting where each ink is printed.
+    //
+    fn paint_color_count_tool(&self, ui: &mut egui::Ui, painter: &egui::Painter) {
+        const SWATCH_SIZE: f32 = 28.0;
+        let Some(dependent_data) = self.get_selected_dependent_data() else {
+            return;
+        };
+        let highlighted = self.highlighted_ink();
+        let corner = egui::Rect::from_min_size(
+            ui.min_rect().min + egui::vec2(0.0, 35.0),
+            egui::vec2(ui.min_rect().width() / 2.0, 200.0),
+        );
+        ui.allocate_ui_at_rect(corner, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (index, ink) in dependent_data.ink_palette().inks().iter().enumerate() {
+                    let (rect, response) = ui.allocate_exact_size(
+                        egui::vec2(SWATCH_SIZE, SWATCH_SIZE),
+                        egui::Sense::hover(),
+                    );
+                    let stroke = if highlighted == Some(index) {
+                        egui::Stroke::new(3.0, egui::Color32::WHITE)
+                    } else {
+                        egui::Stroke::new(1.0, egui::Color32::GRAY)
+                    };
+                    painter.rect(rect, 4.0, ink.color, stroke);
+                    let [r, g, b, _] = ink.color.to_array();
+                    response.on_hover_text(format!(
+                        "#{:02X}{:02X}{:02X} - {}% of the artwork",
+                        r, g, b, ink.percent
+                    ));
+                }
+            });
+        });
+    }
+
+    // Print area calibration for garment mockups.  The print area's outline is drawn
+    // with a handle on each corner.  Dragging a handle moves that corner and leaves the
+    // opposite corner where it is.
+    //
+    fn paint_calibration_tool(
+        &self,
+        mut changes: &mut ChangesToBeMade,
+        ui: &egui::Ui,
+        painter: &egui::Painter,
+        display_size: egui::Vec2,
+    ) {
+        const HANDLE_RADIUS: f32 = 6.0;
+        let TShirtColors::Garment(index) = self.selected_tshirt else {
+            return;
+        };
+
+        let print_area = self.selected_print_area();
+        let rect = self.print_area_on_shirt();
+        let tshirt_to_display = tshirt_to_display(self.central_viewport(display_size));
+        let display_to_tshirt = tshirt_to_display.try_inverse().unwrap();
+
+        let corners = [
+            rect.left_top(),
+            rect.right_top(),
+            rect.right_bottom(),
+            rect.left_bottom(),
+        ];
+        let corners_on_display: Vec<egui::Pos2> = corners
+            .iter()
+            .map(|corner| v3_to_egui(tshirt_to_display * vector![corner.x, corner.y, 1.0]))
+            .collect();
+
+        let calibration_color = egui::Color32::from_rgb(255, 200, 0);
+        let stroke = egui::Stroke::new(2.0, calibration_color);
+        painter.add(egui::Shape::closed_line(corners_on_display.clone(), stroke));
+
+        for (corner, on_display) in corners_on_display.iter().enumerate() {
+            let handle =
+                egui::Rect::from_center_size(*on_display, egui::Vec2::splat(HANDLE_RADIUS * 3.0));
+            let response = ui.interact(
+                handle,
+                ui.id().with(("calibration_corner", corner)),
+                egui::Sense::drag(),
+            );
+            painter.circle(*on_display, HANDLE_RADIUS, calibration_color, stroke);
+
+            if let Some(pointer_pos) = response.interact_pointer_pos() {
+                let on_tshirt = display_to_tshirt * vector![pointer_pos.x, pointer_pos.y, 1.0];
+                let opposite_corner = corners[(corner + 2) % corners.len()];
+                let calibrated = egui::Rect::from_two_pos(v3_to_egui(on_tshirt), opposite_corner);
+                changes += Box::new(move |app: &mut Self| {
+                    if let Some(garment) = app.tshirt_image_storage.garment_mut(index) {
+                        garment.calibration_mut().set(print_area, calibrated);
+                    }
+                });
+            }
+        }
+    }
+
+    // Direct manipulation of the art's placement.  Dragging the art moves it,
+    // dragging a corner scales it around its center, and dragging the round handle
+    // above the art rotates it.  Holding shift snaps the rotation to 15 degrees.
+    //
+    fn paint_placement_tool(
+        &self,
+        mut changes: &mut ChangesToBeMade,
+        ui: &egui::Ui,
+        painter: &egui::Painter,
+        display_size: egui::Vec2,
+    ) {
+        const HANDLE_SIZE: f32 = 12.0;
+        const ROTATE_HANDLE_OFFSET: f32 = 1.0; // inches above the top of the art
+        const ROTATION_SNAP: f32 = 15.0; // degrees
+
+        let art_id = self.displayed_art_id();
+        let print_area = self.selected_print_area();
+        let placement = self.selected_placement();
+        let art_size = self.get_selected_art().size();
+        let size_in_inches = placement.size_in_inches(art_size);
+
+        let art_space_to_display = tshirt_to_display(self.central_viewport(display_size))
+            * self.art_space_to_shirt_matrix();
+        let Some(display_to_art_space) = art_space_to_display.try_inverse() else {
+            return;
+        };
+        let art_to_display = art_space_to_display * placement.art_to_art_space(art_size);
+        let pointer_in_art_space = |pointer_pos: egui::Pos2| {
+            let on_art_space = display_to_art_space * vector![pointer_pos.x, pointer_pos.y, 1.0];
+            egui::Vec2::new(on_art_space.x, on_art_space.y)
+        };
+        let place = move |placed: ArtPlacement| -> ChangeToBeMade {
+            Box::new(move |app: &mut Self| {
+                app.art_storage
+                    .set_placement(art_id, print_area, Some(placed))
+            })
+        };
+
+        let corners: Vec<egui::Pos2> = UNIT_SQUARE_CORNERS
+            .iter()
+            .map(|(x, y)| v3_to_egui(art_to_display * vector![*x, *y, 1.0]))
+            .collect();
+        let top_middle = v3_to_egui(art_to_display * vector![0.5, 0.0, 1.0]);
+        let rotate_handle = v3_to_egui(
+            art_to_display * vector![0.5, -ROTATE_HANDLE_OFFSET / size_in_inches.y, 1.0],
+        );
+
+        let placement_color = egui::Color32::from_rgb(0, 200, 255);
+        let stroke = egui::Stroke::new(2.0, placement_color);
+        painter.add(egui::Shape::closed_line(corners.clone(), stroke));
+        painter.line_segment([top_middle, rotate_handle], stroke);
+
+        // Move.  The art is interacted with first so the handles are on top of it.
+        let art_response = ui.interact(
+            egui::Rect::from_points(&corners),
+            ui.id().with("placement_art"),
+            egui::Sense::drag(),
+        );
+        if art_response.dragged() {
+            let delta = art_response.drag_delta();
+            let delta_in_inches = display_to_art_space * vector![delta.x, delta.y, 0.0];
+            let mut moved = placement;
+            moved.center += egui::Vec2::new(delta_in_inches.x, delta_in_inches.y);
+            changes += place(moved);
+        }
+
+        // Scale
+        for (corner, on_display) in corners.iter().enumerate() {
+            let handle = egui::Rect::from_center_size(*on_display, egui::Vec2::splat(HANDLE_SIZE));
+            let response = ui.interact(
+                handle,
+                ui.id().with(("placement_corner", corner)),
+                egui::Sense::drag(),
+            );
+            painter.rect(handle, 0.0, placement_color, stroke);
+            if let Some(pointer_pos) = response.interact_pointer_pos() {
+                let from_center = pointer_in_art_space(pointer_pos) - placement.center;
+                let half_diagonal = size_in_inches.length() / 2.0;
+                let mut scaled = placement;
+                scaled.width =
+                    (placement.width * from_center.length() / half_diagonal).max(MIN_ART_WIDTH);
+                changes += place(scaled);
+            }
+        }
+
+        // Rotate
+        let handle = egui::Rect::from_center_size(rotate_handle, egui::Vec2::splat(HANDLE_SIZE));
+        let response = ui.interact(
+            handle,
+            ui.id().with("placement_rotate"),
+            egui::Sense::drag(),
+        );
+        painter.circle(rotate_handle, HANDLE_SIZE / 2.0, placement_color, stroke);
+        if let Some(pointer_pos) = response.interact_pointer_pos() {
+            let from_center = pointer_in_art_space(pointer_pos) - placement.center;
+            // The handle points straight up when the art isn't rotated.
+            let mut rotation = from_center.y.atan2(from_center.x).to_degrees() + 90.0;
+            if ui.input(|i| i.modifiers.shift) {
+                rotation = (rotation / ROTATION_SNAP).round() * ROTATION_SNAP;
+            }
+            if rotation > 180.0 {
+                rotation -= 360.0;
+            }
+            let mut rotated = placement;
+            rotated.rotation = rotation;
+            changes += place(rotated);
+        }
+    }
+
     fn central_viewport(&self, display_size: egui::Vec2) -> ViewPort {
         ViewPort {
             zoom: self.move_state.zoom,
             target: self.move_state.target,
             display_size,
-            tshirt_size: self.tshirt_image_storage.tshirt_image_size(),
+            tshirt_size: self
+                .tshirt_image_storage
+                .tshirt_image_size(self.selected_tshirt),
         }
     }
 
@@ -726,38 +2259,125 @@ impl TShirtCheckerApp {
     //
     ////////////////////////////////////////////////////////////////////////////////////
 
+    // The art slot shown in the central panel.  The back of the shirt only has one.
+    //
+    fn displayed_art_id(&self) -> ArtEnum {
+        match self.selected_side {
+            ShirtSide::Front => self.selected_art_id,
+            ShirtSide::Back => ArtEnum::BackArtwork,
+        }
+    }
+
     fn get_selected_art(&self) -> &LoadedImage {
-        self.art_storage.get_art(self.selected_art_id)
+        self.art_storage.get_art(self.displayed_art_id())
     }
 
     fn get_selected_dependent_data(&self) -> Option<&ArtworkDependentData> {
-        self.art_storage.get_dependent_data(self.selected_art_id)
+        self.art_storage.get_dependent_data(self.displayed_art_id())
+    }
+
+    fn selected_print_area(&self) -> PrintArea {
+        self.settings.print_area_for(self.selected_side)
+    }
+
+    // Where the user placed the art, in the print area it's shown in now.
+    //
+    fn user_placement(&self, art_id: ArtEnum) -> Option<ArtPlacement> {
+        self.art_storage
+            .get_user_placement(art_id, self.settings.print_area_for(art_id.side()))
     }
 
     fn is_report_ready(&self, report_type: ReportTypes) -> bool {
         let art = self.get_selected_art();
         let art_dependent_data = self.get_selected_dependent_data();
         let report = self.report_templates.report_type_to_template(report_type);
-        let metric = (report.generate_metric)(art, art_dependent_data);
-        let status = (report.metric_to_status)(metric);
+        let status = report.status(
+            art,
+            &self.selected_placement(),
+            self.selected_shirt_color(),
+            art_dependent_data,
+            self.settings.thresholds(report_type),
+        );
         status != ReportStatus::Unknown
     }
 
     fn are_all_reports_ready(&self) -> bool {
-        self.is_report_ready(ReportTypes::Dpi)
-            && self.is_report_ready(ReportTypes::AreaUsed)
-            && self.is_report_ready(ReportTypes::Bib)
-            && self.is_report_ready(ReportTypes::ThinLines)
-            && self.is_report_ready(ReportTypes::PartialTransparency)
+        ReportTypes::ALL
+            .iter()
+            .all(|report_type| self.is_report_ready(*report_type))
+    }
+
+    fn selected_shirt_color(&self) -> egui::Color32 {
+        self.tshirt_image_storage.shirt_color(self.selected_tshirt)
+    }
+
+    // Every report for the selected artwork.  Only call once the reports are ready.
+    //
+    fn selected_artwork_report(&self) -> ArtworkReport {
+        ArtworkReport::new(
+            self.get_selected_art(),
+            &self.selected_placement(),
+            self.get_selected_dependent_data().unwrap(),
+            &self.report_templates,
+            &self.settings,
+            self.selected_shirt_color(),
+        )
+    }
+
+    fn is_calibrating(&self) -> bool {
+        self.calibrating && matches!(self.selected_tshirt, TShirtColors::Garment(_))
+    }
+
+    // Where the selected print area is on the selected t-shirt or garment
+    //
+    fn print_area_on_shirt(&self) -> egui::Rect {
+        self.tshirt_image_storage
+            .print_area_rect(self.selected_tshirt, self.selected_print_area())
     }
 
     fn art_space_to_shirt_matrix(&self) -> Matrix3<f32> {
-        art_space_to_tshirt(self.tshirt_image_storage.tshirt_image_size())
+        art_space_to_rect(self.selected_print_area(), self.print_area_on_shirt())
+    }
+
+    fn selected_placement(&self) -> ArtPlacement {
+        self.art_storage
+            .get_placement(self.displayed_art_id(), self.selected_print_area())
     }
 
     fn art_to_art_space_matrix(&self) -> Matrix3<f32> {
         let art = self.get_selected_art();
-        art_to_art_space(art.size())
+        self.selected_placement().art_to_art_space(art.size())
+    }
+
+    // The simulated DTG print, if it's being previewed.  Only dark shirts get one.
+    //
+    fn dtg_preview_image(&self) -> Option<LoadedImage> {
+        let shirt_color = self.selected_shirt_color();
+        if !is_dark(shirt_color) {
+            return None;
+        }
+        let with_underbase = match self.dtg_preview {
+            DtgPreview::Off => return None,
+            DtgPreview::NoUnderbase => false,
+            DtgPreview::Underbase => true,
+        };
+        let preview = self.get_selected_dependent_data()?.underbase_preview();
+        Some(preview.preview(shirt_color, with_underbase))
+    }
+
+    // The ink the color count tool is highlighting.  Each ink gets a second.
+    //
+    fn highlighted_ink(&self) -> Option<usize> {
+        if !self.selected_tool.is_active(ReportTypes::ColorCount) {
+            return None;
+        }
+        let inks = self
+            .get_selected_dependent_data()?
+            .ink_palette()
+            .inks()
+            .len();
+        let cycle = self.selected_tool.get_cycles() as usize;
+        (inks > 0).then(|| (cycle / 2) % inks)
     }
 
     //////////////////////////////////////////////////////////////////
@@ -766,16 +2386,18 @@ impl TShirtCheckerApp {
     //
     //////////////////////////////////////////////////////////////////
 
-    fn journal_changes_to_app_state(&mut self, changes: ChangesToBeMade) {
+    fn journal_changes_to_app_state(&mut self, ctx: &egui::Context, changes: ChangesToBeMade) {
         for change in changes.changes.iter() {
             change(self);
         }
-        self.recieve_asyncronous_data();
+        self.recieve_asyncronous_data(ctx);
+        self.recompute_stale_dependent_data(ctx);
+        self.recolor_new_tshirts(ctx);
         self.icons.advance_cycle();
         self.notification_panel.update();
     }
 
-    fn recieve_asyncronous_data(&mut self) {
+    fn recieve_asyncronous_data(&mut self, ctx: &egui::Context) {
         let data_attempt = self.async_data_to_app_receiver.try_recv();
         if data_attempt.is_ok() {
             let loaded_result = data_attempt.unwrap();
@@ -785,10 +2407,198 @@ impl TShirtCheckerApp {
                         self.notification_panel.add_notice(e.msg());
                     }
                 }
-                Ok(f) => {
-                    self.art_storage.set_art(f.art_id, f.art, f.dependent_data);
+                Ok(AsyncPayload::ShirtCatalog(catalog)) => {
+                    self.set_shirt_catalog(catalog);
+                }
+                Ok(AsyncPayload::TShirt(dye, images)) => {
+                    self.tshirt_image_storage
+                        .add_recolored_t_shirt(dye, *images);
+                }
+                Ok(AsyncPayload::Garment(garment)) => {
+                    // Show the new garment, ready to have its print area calibrated.
+                    self.selected_tshirt = self.tshirt_image_storage.add_garment(*garment);
+                    self.calibrating = true;
                     self.selected_tool.reset();
                 }
+                Ok(AsyncPayload::Art(f)) => {
+                    let f = *f;
+                    let art_id = f.art_id;
+                    let has_dependent_data = f.dependent_data.is_some();
+                    if f.new_art {
+                        self.art_storage
+                            .set_new_art(art_id, f.art, f.dependent_data);
+                    } else {
+                        self.art_storage.set_art(art_id, f.art, f.dependent_data);
+                    }
+                    let placement = self.user_placement(art_id);
+                    let out_of_date = self
+                        .art_storage
+                        .get_dependent_data(art_id)
+                        .is_some_and(|dd| !dd.computed_with(&self.settings, placement));
+                    if out_of_date {
+                        // The settings or the placement changed while the data was being
+                        // computed.  Keep the art, but compute the data again if it's
+                        // still needed.
+                        self.art_storage.forget_dependent_data_for(art_id);
+                        if art_id == self.displayed_art_id() || self.comparing {
+                            crate::async_tasks::cache_in_dependent_data(
+                                ctx,
+                                self.art_storage.get_art(art_id),
+                                art_id,
+                                placement,
+                                &self.settings,
+                                &self.async_data_to_app_sender,
+                            );
+                        }
+                    }
+                    if has_dependent_data || out_of_date {
+                        self.selected_tool.reset();
+                    }
+                }
+            }
+        }
+    }
+
+    // Replace the report settings.  If the change affects the cached report data
+    // (i.e., the thin line width changed) the cached data is thrown away and
+    // recomputed for the selected artwork.
+    //
+    fn set_settings(&mut self, settings: ReportSettings, ctx: &egui::Context) {
+        self.settings = settings;
+        let placement = self.user_placement(self.displayed_art_id());
+        let out_of_date = self
+            .get_selected_dependent_data()
+            .is_some_and(|dd| !dd.computed_with(&self.settings, placement));
+        if out_of_date {
+            self.art_storage.forget_dependent_data();
+            self.selected_tool.reset();
+            if self.comparing {
+                self.cache_in_missing_dependent_data(ctx);
+            } else {
+                crate::async_tasks::cache_in_dependent_data(
+                    ctx,
+                    self.get_selected_art(),
+                    self.displayed_art_id(),
+                    self.user_placement(self.displayed_art_id()),
+                    &self.settings,
+                    &self.async_data_to_app_sender,
+                );
+            }
+        }
+    }
+
+    // Switch between the front and back of the t-shirt, computing the report data for
+    // the newly displayed art if it hasn't been computed yet.
+    //
+    fn set_side(&mut self, side: ShirtSide, ctx: &egui::Context) {
+        self.selected_side = side;
+        self.selected_tool.reset();
+        if self.get_selected_dependent_data().is_none() {
+            crate::async_tasks::cache_in_dependent_data(
+                ctx,
+                self.get_selected_art(),
+                self.displayed_art_id(),
+                self.user_placement(self.displayed_art_id()),
+                &self.settings,
+                &self.async_data_to_app_sender,
+            );
+        }
+    }
+
+    // Show a piece of art on a shirt, i.e., after picking it in the comparison window.
+    //
+    fn show_art_on_shirt(&mut self, art_id: ArtEnum, shirt: TShirtColors, ctx: &egui::Context) {
+        if art_id.side() == ShirtSide::Front {
+            self.selected_art_id = art_id;
+        }
+        self.selected_tshirt = shirt;
+        self.set_side(art_id.side(), ctx);
+    }
+
+    // Compute the report data for every art slot that doesn't have it.  The comparison
+    // window needs it for all of them, not just the displayed art.
+    //
+    fn cache_in_missing_dependent_data(&self, ctx: &egui::Context) {
+        for art_id in ArtEnum::ALL {
+            if self.art_storage.get_dependent_data(art_id).is_none() {
+                crate::async_tasks::cache_in_dependent_data(
+                    ctx,
+                    self.art_storage.get_art(art_id),
+                    art_id,
+                    self.user_placement(art_id),
+                    &self.settings,
+                    &self.async_data_to_app_sender,
+                );
+            }
+        }
+    }
+
+    // Moving or scaling the art changes its DPI, and with it how wide a thin line is.
+    // Throw away the displayed art's data once it no longer matches, and compute it
+    // again.
+    //
+    fn recompute_stale_dependent_data(&mut self, ctx: &egui::Context) {
+        let art_id = self.displayed_art_id();
+        let placement = self.user_placement(art_id);
+        let out_of_date = self
+            .get_selected_dependent_data()
+            .is_some_and(|dd| !dd.computed_with(&self.settings, placement));
+        if out_of_date {
+            self.art_storage.forget_dependent_data_for(art_id);
+            self.selected_tool.reset();
+            crate::async_tasks::cache_in_dependent_data(
+                ctx,
+                self.get_selected_art(),
+                art_id,
+                placement,
+                &self.settings,
+                &self.async_data_to_app_sender,
+            );
+        }
+    }
+
+    //
+    // Schedule an asyncronous task to recolor any t-shirts that were added, i.e., a
+    // new catalog was loaded or a custom color was picked.
+    //
+    fn recolor_new_tshirts(&mut self, ctx: &egui::Context) {
+        let dyes = self.tshirt_image_storage.take_t_shirts_to_recolor();
+        if !dyes.is_empty() {
+            crate::async_tasks::recolor_tshirts(
+                ctx,
+                self.tshirt_image_storage.blue_t_shirt(),
+                dyes,
+                &self.async_data_to_app_sender,
+            );
+        }
+    }
+
+    fn set_shirt_catalog(&mut self, catalog: ShirtColorCatalog) {
+        self.tshirt_image_storage.set_catalog(catalog);
+        if let TShirtColors::Catalog(_) = self.selected_tshirt {
+            self.selected_tshirt = TShirtColors::Catalog(0);
+        }
+    }
+
+    fn remove_custom_color(&mut self, color: egui::Color32) {
+        self.tshirt_image_storage.remove_custom_color(color);
+        if self.selected_tshirt == TShirtColors::Custom(color) {
+            self.selected_tshirt = TShirtColors::Catalog(0);
+        }
+    }
+
+    // Remove an imported garment.  Garments after it move down a slot, so the
+    // selection has to follow them.
+    //
+    fn remove_garment(&mut self, index: usize) {
+        self.tshirt_image_storage.remove_garment(index);
+        if let TShirtColors::Garment(selected) = self.selected_tshirt {
+            match selected.cmp(&index) {
+                std::cmp::Ordering::Equal => self.selected_tshirt = TShirtColors::Catalog(0),
+                std::cmp::Ordering::Greater => {
+                    self.selected_tshirt = TShirtColors::Garment(selected - 1)
+                }
+                std::cmp::Ordering::Less => {}
             }
         }
     }
@@ -797,7 +2607,7 @@ impl TShirtCheckerApp {
         let mut time_to_repaint: u32 = u32::MAX;
         time_to_repaint = time_to_repaint.min(self.notification_panel.time_to_update());
 
-        let display_loading_animation_instead_of_tools = self.are_all_reports_ready();
+        let display_loading_animation_instead_of_tools = !self.are_all_reports_ready();
         if display_loading_animation_instead_of_tools {
             time_to_repaint = time_to_repaint.min(ICON_LOAD_ANIMATION_IN_MILLIS);
         }
@@ -809,6 +2619,12 @@ impl TShirtCheckerApp {
             || self.selected_tool.is_active(ReportTypes::ThinLines)
             || self.selected_tool.is_active(ReportTypes::Dpi)
             || self.selected_tool.is_active(ReportTypes::Bib)
+            || self.selected_tool.is_active(ReportTypes::ColorCount)
+            || self.selected_tool.is_active(ReportTypes::LowContrast)
+            || self.selected_tool.is_active(ReportTypes::Specks)
+            || self.selected_tool.is_active(ReportTypes::Background)
+            || self.selected_tool.is_active(ReportTypes::Fringe)
+            || self.selected_tool.is_active(ReportTypes::Margins)
         {
             time_to_repaint = time_to_repaint.min(self.selected_tool.time_to_next_epoch());
         }
@@ -819,6 +2635,23 @@ impl TShirtCheckerApp {
     }
 }
 
+// The art may be rotated, so it's painted as a textured quad rather than an image in
+// a rectangle.
+//
+fn paint_art_quad(painter: &egui::Painter, texture: egui::TextureId, art_to_display: Matrix3<f32>) {
+    let mut mesh = egui::Mesh::with_texture(texture);
+    for (x, y) in UNIT_SQUARE_CORNERS {
+        mesh.vertices.push(egui::epaint::Vertex {
+            pos: v3_to_egui(art_to_display * vector![x, y, 1.0]),
+            uv: egui::Pos2 { x, y },
+            color: egui::Color32::WHITE,
+        });
+    }
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(0, 2, 3);
+    painter.add(egui::Shape::mesh(mesh));
+}
+
 fn mtexts(text: &String, scale: f32) -> egui::widget_text::RichText {
     egui::widget_text::RichText::from(text).size(25.0 * scale)
 }
@@ -0,0 +1,220 @@
+//! Art placement
+//!
+//! By default the art is scaled to fill the print area and centered in it.  Users can
+//! also move, scale and rotate the art to preview it printed at a particular size or
+//! spot (i.e., 8 inches wide and lower on the chest).  Placements are in inches,
+//! measured from the top left corner of the print area.
+
+extern crate nalgebra as na;
+use crate::loaded_image::*;
+use crate::math::*;
+use crate::print_area::*;
+use na::{matrix, Matrix3};
+
+// The embedded resolution and the resolution the art is printed at are considered to
+// agree if they're within this fraction of each other.
+const DPI_TOLERANCE: f32 = 0.05;
+
+/// Where the art is printed within the print area
+#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArtPlacement {
+    /// Center of the art, in inches from the top left corner of the print area
+    pub center: egui::Vec2,
+    /// How wide the art is printed, in inches.  The height follows the art's aspect
+    /// ratio.
+    pub width: f32,
+    /// Clockwise rotation, in degrees
+    pub rotation: f32,
+}
+
+impl ArtPlacement {
+    /// Scale the art to fill the print area and center it.
+    pub fn fit(art_size: egui::Vec2, print_area: PrintArea) -> Self {
+        let canvas = egui::Rect::from_min_size(egui::Pos2::ZERO, art_size);
+        Self::fit_content(art_size, canvas, print_area)
+    }
+
+    /// Scale the art so its content (in pixels of the art) fills the print area, and
+    /// center the content.  Transparent margins around the content hang off the print
+    /// area.  This is the default.
+    pub fn fit_content(art_size: egui::Vec2, content: egui::Rect, print_area: PrintArea) -> Self {
+        let content_in_inches = art_size_in_inches(content.size(), print_area);
+        let inches_per_pixel = content_in_inches.x / content.width();
+        let content_offset = art_size / 2.0 - content.center().to_vec2();
+        Self {
+            center: print_area.size_in_inches() / 2.0 + content_offset * inches_per_pixel,
+            width: art_size.x * inches_per_pixel,
+            rotation: 0.0,
+        }
+    }
+
+    /// Print the art at the size its embedded resolution says it should be, centered
+    /// in the print area.
+    pub fn at_dpi(art_size: egui::Vec2, dpi: f32, print_area: PrintArea) -> Self {
+        Self {
+            center: print_area.size_in_inches() / 2.0,
+            width: art_size.x / dpi,
+            rotation: 0.0,
+        }
+    }
+
+    /// The placement of the art cropped to crop (in pixels of the art) that prints the
+    /// remaining pixels exactly where they were.
+    pub fn crop(&self, art_size: egui::Vec2, crop: egui::Rect) -> Self {
+        let inches_per_pixel = self.width / art_size.x;
+        let offset = (crop.center().to_vec2() - art_size / 2.0) * inches_per_pixel;
+        let rotate = egui::emath::Rot2::from_angle(self.rotation.to_radians());
+        Self {
+            center: self.center + rotate * offset,
+            width: crop.width() * inches_per_pixel,
+            rotation: self.rotation,
+        }
+    }
+
+    /// The resolution the art is printed at, in dots per inch
+    pub fn dpi(&self, art_size: egui::Vec2) -> f32 {
+        art_size.x / self.width
+    }
+
+    /// Is the art printed at a different resolution than the one embedded in the
+    /// image file?
+    pub fn disagrees_with(&self, art_size: egui::Vec2, embedded_dpi: f32) -> bool {
+        (self.dpi(art_size) - embedded_dpi).abs() > embedded_dpi * DPI_TOLERANCE
+    }
+
+    /// A warning if the art is printed at a different resolution than the one embedded
+    /// in its image file
+    pub fn embedded_dpi_warning(&self, art: &LoadedImage) -> Option<String> {
+        let embedded_dpi = art.embedded_dpi()?;
+        self.disagrees_with(art.size(), embedded_dpi).then(|| {
+            format!(
+                "The image file says it's {:.0} DPI, but it's being printed at {:.0} DPI.",
+                embedded_dpi,
+                self.dpi(art.size())
+            )
+        })
+    }
+
+    /// The size the art is printed at, in inches
+    pub fn size_in_inches(&self, art_size: egui::Vec2) -> egui::Vec2 {
+        egui::Vec2::new(self.width, self.width * art_size.y / art_size.x)
+    }
+
+    /// How much of the print area part of the art covers, in percent.  The part's size
+    /// is in pixels of the art.
+    pub fn percent_of_print_area(
+        &self,
+        art_size: egui::Vec2,
+        part_size: egui::Vec2,
+        print_area: PrintArea,
+    ) -> f32 {
+        let inches_per_pixel = self.width / art_size.x;
+        let print_size = print_area.size_in_inches();
+        100.0 * part_size.x * part_size.y * inches_per_pixel * inches_per_pixel
+            / (print_size.x * print_size.y)
+    }
+
+    /// Transforms from the artwork's space, where (0,0) is the top left corner of the
+    /// art and (1,1) is the bottom right corner, to "t shirt artwork space".
+    pub fn art_to_art_space(&self, art_size: egui::Vec2) -> Matrix3<f32> {
+        let size = self.size_in_inches(art_size);
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+
+        let center_art_at_origin: Matrix3<f32> = matrix![ 1.0, 0.0, -0.5;
+                                                          0.0, 1.0, -0.5;
+                                                          0.0, 0.0, 1.0 ];
+        let scale_to_inches: Matrix3<f32> = matrix![ size.x, 0.0,    0.0;
+                                                     0.0,    size.y, 0.0;
+                                                     0.0,    0.0,    1.0 ];
+        let rotate: Matrix3<f32> = matrix![ cos, -sin, 0.0;
+                                            sin,  cos, 0.0;
+                                            0.0,  0.0, 1.0 ];
+        let move_to_center: Matrix3<f32> = matrix![ 1.0, 0.0, self.center.x;
+                                                    0.0, 1.0, self.center.y;
+                                                    0.0, 0.0, 1.0 ];
+
+        move_to_center * rotate * scale_to_inches * center_art_at_origin
+    }
+}
+
+#[cfg(test)]
+mod art_placement_should {
+    use super::*;
+    use na::vector;
+
+    #[test]
+    fn fit_the_art_to_the_print_area_by_default() {
+        let art_size = egui::Vec2::new(2200.0, 1400.0);
+        let placement = ArtPlacement::fit(art_size, PrintArea::FullFront);
+        assert_eq!(
+            egui::Vec2::new(11.0, 7.0),
+            placement.size_in_inches(art_size)
+        );
+        let expected = art_to_art_space(art_size, PrintArea::FullFront);
+        let actual = placement.art_to_art_space(art_size);
+        assert!((expected - actual).norm() < 1e-5);
+    }
+
+    #[test]
+    fn fit_the_content_not_the_margins() {
+        let art_size = egui::Vec2::new(3000.0, 3000.0);
+        // 1100 x 700 pixels of art in the top left of a big transparent canvas
+        let content = egui::Rect::from_min_max(egui::pos2(100.0, 200.0), egui::pos2(1200.0, 900.0));
+        let placement = ArtPlacement::fit_content(art_size, content, PrintArea::FullFront);
+        assert_eq!(100.0, placement.dpi(art_size));
+        let matrix = placement.art_to_art_space(art_size);
+        let top_left = matrix * vector![100.0 / 3000.0, 200.0 / 3000.0, 1.0];
+        assert!((top_left - vector![0.0, 3.5, 1.0]).norm() < 1e-4);
+        let used = placement.percent_of_print_area(art_size, content.size(), PrintArea::FullFront);
+        assert!((used - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn print_at_the_embedded_size() {
+        let art_size = egui::Vec2::new(1500.0, 600.0);
+        let placement = ArtPlacement::at_dpi(art_size, 150.0, PrintArea::FullFront);
+        assert_eq!(
+            egui::Vec2::new(10.0, 4.0),
+            placement.size_in_inches(art_size)
+        );
+        assert!(!placement.disagrees_with(art_size, 150.0));
+        assert!(!placement.disagrees_with(art_size, 145.0));
+        assert!(placement.disagrees_with(art_size, 300.0));
+    }
+
+    #[test]
+    fn move_scale_and_rotate_the_art() {
+        let art_size = egui::Vec2::new(1000.0, 500.0);
+        let placement = ArtPlacement {
+            center: egui::Vec2::new(5.0, 8.0),
+            width: 8.0,
+            rotation: 90.0,
+        };
+        let matrix = placement.art_to_art_space(art_size);
+        let center = matrix * vector![0.5, 0.5, 1.0];
+        assert!((center - vector![5.0, 8.0, 1.0]).norm() < 1e-5);
+        // The top left corner ends up on the top right after a quarter turn.
+        let top_left = matrix * vector![0.0, 0.0, 1.0];
+        assert!((top_left - vector![7.0, 4.0, 1.0]).norm() < 1e-5);
+    }
+
+    #[test]
+    fn keep_cropped_art_where_it_was() {
+        let art_size = egui::Vec2::new(1000.0, 500.0);
+        let placement = ArtPlacement {
+            center: egui::Vec2::new(5.0, 8.0),
+            width: 8.0,
+            rotation: 30.0,
+        };
+        let crop = egui::Rect::from_min_max(egui::pos2(100.0, 50.0), egui::pos2(400.0, 450.0));
+        let cropped = placement.crop(art_size, crop);
+        assert!((placement.dpi(art_size) - cropped.dpi(crop.size())).abs() < 1e-3);
+
+        let matrix = placement.art_to_art_space(art_size);
+        let cropped_matrix = cropped.art_to_art_space(crop.size());
+        let top_left = matrix * vector![0.1, 0.1, 1.0];
+        let bottom_right = matrix * vector![0.4, 0.9, 1.0];
+        assert!((cropped_matrix * vector![0.0, 0.0, 1.0] - top_left).norm() < 1e-4);
+        assert!((cropped_matrix * vector![1.0, 1.0, 1.0] - bottom_right).norm() < 1e-4);
+    }
+}
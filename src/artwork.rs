@@ -1,36 +1,118 @@
 //! Tools for creating and managing artwork analysis data (dependant data)
 //! given the t-shirt artwork as input.
 
+use crate::art_placement::*;
+use crate::background::*;
+use crate::fringe::*;
+use crate::halftone::*;
 use crate::image_utils::*;
+use crate::ink_colors::*;
+use crate::knockout::*;
 use crate::loaded_image::*;
-use crate::math::*;
-use nalgebra::dvector;
+use crate::margins::*;
+use crate::print_area::*;
+use crate::report_settings::*;
+use crate::shirt_contrast::*;
+use crate::specks::*;
+use crate::underbase::*;
+use std::sync::Mutex;
 
-/// Artwork slot - one of three.
+/// Artwork slot - one of three for the front of the T-Shirt, or the back.
 #[derive(PartialEq, Copy, Clone)]
 pub enum ArtEnum {
     Artwork0,
     Artwork1,
     Artwork2,
+    BackArtwork,
+}
+
+impl ArtEnum {
+    /// Every art slot, front first
+    pub const ALL: [ArtEnum; 4] = [
+        ArtEnum::Artwork0,
+        ArtEnum::Artwork1,
+        ArtEnum::Artwork2,
+        ArtEnum::BackArtwork,
+    ];
+
+    /// A user facing name for the slot
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArtEnum::Artwork0 => "Front art 1",
+            ArtEnum::Artwork1 => "Front art 2",
+            ArtEnum::Artwork2 => "Front art 3",
+            ArtEnum::BackArtwork => "Back art",
+        }
+    }
+
+    /// The side of the T-Shirt the slot's art is printed on
+    pub fn side(&self) -> ShirtSide {
+        match self {
+            ArtEnum::BackArtwork => ShirtSide::Back,
+            _ => ShirtSide::Front,
+        }
+    }
 }
 
 /// Analysis data that depends on the t-shirt artwork
 pub struct ArtworkDependentData {
+    // The art, for data that's computed when it's asked for
+    artwork: LoadedImage,
+
+    // Data for Margins report/ tool, and for placing the art by its content
+    margin_check: MarginCheck,
+
     // Data for DPI tool
     dpi_top_hot_spots: Vec<HotSpot>,
 
     // Data for Partial Transparency report/ tool
     partial_transparency_percent: u32,
     partial_transparency_problems: LoadedImage,
-    partial_transparency_fixed: LoadedImage,
+    // The art with partial transparency fixed, and the settings it was fixed with
+    partial_transparency_fixed: Mutex<(AlphaFixSettings, LoadedImage)>,
+
+    // Data for Fringe report/ tool
+    fringe_check: FringeCheck,
 
     // Data for Bib report/ tool
     bib_opaque_percent: u32,
     bib_opaque_mask: LoadedImage,
 
+    // Data for Background report/ tool
+    background_check: BackgroundCheck,
+
+    // Data for Color Count report/ tool
+    ink_palette: InkPalette,
+
+    // Data for Low Contrast report/ tool.  The shirt color isn't known until the
+    // report is shown, so this is only the art's side of it.
+    shirt_contrast: ShirtContrast,
+
+    // Data for the DTG underbase preview and mask export
+    underbase_preview: UnderbasePreview,
+
+    // Data for the halftone fix's before/ after preview
+    halftone_preview: HalftonePreview,
+
+    // Data for the shirt color knockout preview
+    knockout_preview: KnockoutPreview,
+
     // Data for Thin Line tool
     thin_line_percent: u32,
     thin_line_problems: LoadedImage,
+    // The thinnest printable line, in pixels of the art, at the size the art was
+    // placed at when the data was computed.
+    thin_line_pixels: usize,
+
+    // Data for Specks report/ tool.  Every island of ink in the art, so the specks
+    // can be picked out for whatever size the art is placed at.
+    islands: Vec<Speck>,
+
+    // The settings the data was computed with
+    thin_line_fraction: u32,
+    max_speck_area: f32,
+    side: ShirtSide,
+    print_area: PrintArea,
 }
 
 impl ArtworkDependentData {
@@ -44,77 +126,180 @@ impl ArtworkDependentData {
     // When run in web assembly on a browser it's more of a co-operative multi-tasking
     // model.
     //
-    // The context_switch().await calls let the web assembly build know this is a spot where
+    // The yield_now().await calls let the web assembly build know this is a spot where
     // it can give something else a change to run
     //
-    pub async fn new(ctx: &egui::Context, artwork: &LoadedImage) -> Self {
+    // Only the artwork's pixels are used, so no egui::Context (and no GPU textures) are
+    // needed.  Textures get created later if the GUI displays any of the images.
+    //
+    // The thin line data depends on the size the art is printed at.  placement is
+    // where the user placed the art, or None if it fills the print area.
+    //
+    pub async fn new(
+        artwork: &LoadedImage,
+        settings: &ReportSettings,
+        side: ShirtSide,
+        placement: Option<ArtPlacement>,
+    ) -> Self {
+        let print_area = settings.print_area_for(side);
+        let margin_check = MarginCheck::new(artwork);
+
         //
         // Compute interesting hot spots for the DPI tool using a heat map based
         // on a simple edge detection algorithm,
         //
-        crate::async_tasks::context_switch(ctx).await;
-        let heat_map = heat_map_from_image(artwork, "heatmap", ctx);
+        crate::async_tasks::yield_now().await;
+        let heat_map = heat_map_from_image(artwork, "heatmap");
         let dpi_top_hot_spots = hot_spots_from_heat_map(&heat_map);
 
         //
         // Create images and metrics for the partial transparency tool
         //
-        crate::async_tasks::context_switch(ctx).await;
+        crate::async_tasks::yield_now().await;
         let partial_transparency_problems: LoadedImage = load_image_from_existing_image(
             artwork,
             &flag_alpha_for_shirt,
             "partial_transparency_problems",
-            ctx,
         );
-        crate::async_tasks::context_switch(ctx).await;
-        let partial_transparency_fixed: LoadedImage = load_image_from_existing_image(
-            artwork,
-            &correct_alpha_for_tshirt,
-            "partial_transparency_fixed",
-            ctx,
-        );
-        crate::async_tasks::context_switch(ctx).await;
+        crate::async_tasks::yield_now().await;
+        let partial_transparency_fixed =
+            correct_alpha(artwork, &settings.alpha_fix, "partial_transparency_fixed");
+        crate::async_tasks::yield_now().await;
         let partial_transparency_percent = compute_bad_tpixels(artwork.pixels());
 
+        //
+        // Look for anti-aliased edges blended with a matte, for the fringe report & tool
+        //
+        crate::async_tasks::yield_now().await;
+        let fringe_check = FringeCheck::new(artwork);
+
         //
         // Compute images and metrics for the bib report.
         //
-        crate::async_tasks::context_switch(ctx).await;
+        crate::async_tasks::yield_now().await;
         let bib_opaque_percent = compute_percent_opaque(artwork.pixels());
-        crate::async_tasks::context_switch(ctx).await;
-        let bib_opaque_mask =
-            load_image_from_existing_image(artwork, &opaque_to_mask, "bib_mask", ctx);
+        crate::async_tasks::yield_now().await;
+        let bib_opaque_mask = load_image_from_existing_image(artwork, &opaque_to_mask, "bib_mask");
+
+        //
+        // Look for a background left around the art, for the background report & tool
+        //
+        crate::async_tasks::yield_now().await;
+        let background_check = BackgroundCheck::new(artwork);
+
+        //
+        // Cluster the art's colors into inks for the color count report & tool
+        //
+        crate::async_tasks::yield_now().await;
+        let ink_palette = InkPalette::new(artwork);
+        crate::async_tasks::yield_now().await;
+        let shirt_contrast = ShirtContrast::new(artwork);
+
+        //
+        // Compute the underbase mask for DTG printing on dark shirts
+        //
+        crate::async_tasks::yield_now().await;
+        let underbase_preview = UnderbasePreview::new(artwork);
+
+        //
+        // Find the partial transparency to show in the halftone fix preview
+        //
+        crate::async_tasks::yield_now().await;
+        let halftone_preview = HalftonePreview::new(artwork);
+        let knockout_preview = KnockoutPreview::new(artwork);
 
         //
         // Compute images and metrics for the thin line report & tool
         //
-        crate::async_tasks::context_switch(ctx).await;
-        let top_corner = art_to_art_space(artwork.size()) * dvector![0.0, 0.0, 1.0];
-        let bot_corner = art_to_art_space(artwork.size()) * dvector![1.0, 1.0, 1.0];
-        let dim_in_inches = bot_corner - top_corner;
-        let dpi = artwork.size().x / dim_in_inches.x;
-        // going to say that 1/64 inches is too thin for now
-        let dots = (dpi * (1.0 / 64.0)).ceil() as usize;
+        crate::async_tasks::yield_now().await;
+        let placement = placement.unwrap_or_else(|| {
+            ArtPlacement::fit_content(artwork.size(), margin_check.content(), print_area)
+        });
+        let dots = settings.thin_line_pixels(placement.dpi(artwork.size()));
 
-        let thin_line_problems = flag_thin_lines(artwork, ctx, dots).await;
-        crate::async_tasks::context_switch(ctx).await;
+        let thin_line_problems = flag_thin_lines(artwork, dots).await;
+        crate::async_tasks::yield_now().await;
         let thin_line_percent = compute_percent_diff(&thin_line_problems, artwork);
 
+        //
+        // Find the islands of ink for the specks report & tool
+        //
+        crate::async_tasks::yield_now().await;
+        let islands = find_specks(artwork, usize::MAX);
+
         Self {
+            artwork: artwork.clone(),
+
+            margin_check,
+
             dpi_top_hot_spots,
 
             partial_transparency_percent,
             partial_transparency_problems,
-            partial_transparency_fixed,
+            partial_transparency_fixed: Mutex::new((
+                settings.alpha_fix,
+                partial_transparency_fixed,
+            )),
+
+            fringe_check,
 
             bib_opaque_percent,
             bib_opaque_mask,
 
+            background_check,
+
+            ink_palette,
+            shirt_contrast,
+            underbase_preview,
+            halftone_preview,
+            knockout_preview,
+
             thin_line_percent,
             thin_line_problems,
+            thin_line_pixels: dots,
+
+            islands,
+
+            thin_line_fraction: settings.thin_line_fraction,
+            max_speck_area: settings.max_speck_area,
+            side,
+            print_area,
         }
     }
 
+    /// Was the data computed with these settings, for art placed here?  If not, it's
+    /// out of date.  A placement of None means the art fills the print area.
+    pub fn computed_with(
+        &self,
+        settings: &ReportSettings,
+        placement: Option<ArtPlacement>,
+    ) -> bool {
+        self.thin_line_fraction == settings.thin_line_fraction
+            && self.max_speck_area == settings.max_speck_area
+            && self.print_area == settings.print_area_for(self.side)
+            && self.thin_line_pixels == settings.thin_line_pixels(self.placement_dpi(placement))
+    }
+
+    // The DPI the art is printed at.  Art without a placement fills the print area.
+    //
+    fn placement_dpi(&self, placement: Option<ArtPlacement>) -> f32 {
+        let art_size = self.artwork.size();
+        placement
+            .unwrap_or_else(|| {
+                ArtPlacement::fit_content(art_size, self.margin_check.content(), self.print_area)
+            })
+            .dpi(art_size)
+    }
+
+    /// The print area the data was computed for
+    pub fn print_area(&self) -> PrintArea {
+        self.print_area
+    }
+
+    pub fn margin_check(&self) -> &MarginCheck {
+        &self.margin_check
+    }
+
     pub fn dpi_top_hot_spots(&self) -> &Vec<HotSpot> {
         &self.dpi_top_hot_spots
     }
@@ -127,8 +312,20 @@ impl ArtworkDependentData {
         &self.partial_transparency_problems
     }
 
-    pub fn partial_transparency_fixed(&self) -> &LoadedImage {
-        &self.partial_transparency_fixed
+    /// The art as the partial transparency fix would leave it
+    pub fn partial_transparency_fixed(&self, fix: &AlphaFixSettings) -> LoadedImage {
+        let mut fixed = self.partial_transparency_fixed.lock().unwrap();
+        if fixed.0 != *fix {
+            *fixed = (
+                *fix,
+                correct_alpha(&self.artwork, fix, "partial_transparency_fixed"),
+            );
+        }
+        fixed.1.clone()
+    }
+
+    pub fn fringe_check(&self) -> &FringeCheck {
+        &self.fringe_check
     }
 
     pub fn bib_opaque_percent(&self) -> u32 {
@@ -139,6 +336,30 @@ impl ArtworkDependentData {
         &self.bib_opaque_mask
     }
 
+    pub fn background_check(&self) -> &BackgroundCheck {
+        &self.background_check
+    }
+
+    pub fn ink_palette(&self) -> &InkPalette {
+        &self.ink_palette
+    }
+
+    pub fn shirt_contrast(&self) -> &ShirtContrast {
+        &self.shirt_contrast
+    }
+
+    pub fn underbase_preview(&self) -> &UnderbasePreview {
+        &self.underbase_preview
+    }
+
+    pub fn halftone_preview(&self) -> &HalftonePreview {
+        &self.halftone_preview
+    }
+
+    pub fn knockout_preview(&self) -> &KnockoutPreview {
+        &self.knockout_preview
+    }
+
     pub fn thin_line_percent(&self) -> u32 {
         self.thin_line_percent
     }
@@ -146,39 +367,79 @@ impl ArtworkDependentData {
     pub fn thin_line_problems(&self) -> &LoadedImage {
         &self.thin_line_problems
     }
+
+    pub fn thin_line_pixels(&self) -> usize {
+        self.thin_line_pixels
+    }
+
+    /// The islands of ink too small to print, for art printed at dpi
+    pub fn specks(&self, dpi: f32) -> impl Iterator<Item = &Speck> {
+        let max_pixels = self.speck_pixels(dpi);
+        self.islands
+            .iter()
+            .filter(move |island| island.pixels < max_pixels)
+    }
+
+    /// The largest speck, in pixels of the art, for art printed at dpi
+    pub fn speck_pixels(&self, dpi: f32) -> usize {
+        (self.max_speck_area * dpi * dpi).round() as usize
+    }
 }
 
 pub struct ArtStorage {
     artwork_0: LoadedImage,
     artwork_1: LoadedImage,
     artwork_2: LoadedImage,
+    back_artwork: LoadedImage,
     art_dependent_data_0: std::option::Option<ArtworkDependentData>,
     art_dependent_data_1: std::option::Option<ArtworkDependentData>,
     art_dependent_data_2: std::option::Option<ArtworkDependentData>,
+    back_art_dependent_data: std::option::Option<ArtworkDependentData>,
+    // The rectangle around each piece of art's non-transparent pixels.  Found when the
+    // art is set, so the default placement doesn't move when the dependent data comes
+    // and goes.
+    content_0: egui::Rect,
+    content_1: egui::Rect,
+    content_2: egui::Rect,
+    back_content: egui::Rect,
+    // Where the user placed each piece of art, and the print area it was placed in.
+    // None means the art fills the print area.
+    placement_0: Option<(PrintArea, ArtPlacement)>,
+    placement_1: Option<(PrintArea, ArtPlacement)>,
+    placement_2: Option<(PrintArea, ArtPlacement)>,
+    back_placement: Option<(PrintArea, ArtPlacement)>,
 }
 
 impl ArtStorage {
-    pub fn new(ctx: &egui::Context) -> Self {
+    pub fn new() -> Self {
         let artwork_0: LoadedImage = load_image_from_trusted_source(
             include_bytes!("../assets/test_artwork.png"),
             "artwork_0",
-            ctx,
         );
         let artwork_1: LoadedImage =
-            load_image_from_trusted_source(include_bytes!("../assets/tux.svg"), "artwork_1", ctx);
-        let artwork_2: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/rust_crab.svg"),
-            "artwork_2",
-            ctx,
-        );
+            load_image_from_trusted_source(include_bytes!("../assets/tux.svg"), "artwork_1");
+        let artwork_2: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/rust_crab.svg"), "artwork_2");
+        let back_artwork: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/tux.svg"), "back_artwork");
 
         Self {
             art_dependent_data_0: None,
             art_dependent_data_1: None,
             art_dependent_data_2: None,
+            back_art_dependent_data: None,
+            placement_0: None,
+            placement_1: None,
+            placement_2: None,
+            back_placement: None,
+            content_0: MarginCheck::new(&artwork_0).content(),
+            content_1: MarginCheck::new(&artwork_1).content(),
+            content_2: MarginCheck::new(&artwork_2).content(),
+            back_content: MarginCheck::new(&back_artwork).content(),
             artwork_0,
             artwork_1,
             artwork_2,
+            back_artwork,
         }
     }
     pub fn get_dependent_data(&self, art_id: ArtEnum) -> Option<&ArtworkDependentData> {
@@ -186,6 +447,7 @@ impl ArtStorage {
             ArtEnum::Artwork0 => self.art_dependent_data_0.as_ref(),
             ArtEnum::Artwork1 => self.art_dependent_data_1.as_ref(),
             ArtEnum::Artwork2 => self.art_dependent_data_2.as_ref(),
+            ArtEnum::BackArtwork => self.back_art_dependent_data.as_ref(),
         }
     }
 
@@ -194,28 +456,247 @@ impl ArtStorage {
             ArtEnum::Artwork0 => &self.artwork_0,
             ArtEnum::Artwork1 => &self.artwork_1,
             ArtEnum::Artwork2 => &self.artwork_2,
+            ArtEnum::BackArtwork => &self.back_artwork,
+        }
+    }
+
+    /// Throw away the dependent data for every art slot, i.e., because the settings
+    /// used to compute it have changed.
+    pub fn forget_dependent_data(&mut self) {
+        self.art_dependent_data_0 = None;
+        self.art_dependent_data_1 = None;
+        self.art_dependent_data_2 = None;
+        self.back_art_dependent_data = None;
+    }
+
+    /// Throw away the dependent data for one piece of art, i.e., when moving or
+    /// scaling the art made it out of date.
+    pub fn forget_dependent_data_for(&mut self, art_id: ArtEnum) {
+        match art_id {
+            ArtEnum::Artwork0 => self.art_dependent_data_0 = None,
+            ArtEnum::Artwork1 => self.art_dependent_data_1 = None,
+            ArtEnum::Artwork2 => self.art_dependent_data_2 = None,
+            ArtEnum::BackArtwork => self.back_art_dependent_data = None,
+        }
+    }
+
+    /// Where the user placed the art in the print area.  None if they haven't, or
+    /// placed it in a different print area.
+    pub fn get_user_placement(
+        &self,
+        art_id: ArtEnum,
+        print_area: PrintArea,
+    ) -> Option<ArtPlacement> {
+        match self.placed(art_id) {
+            Some((placed_in, placement)) if placed_in == print_area => Some(placement),
+            _ => None,
+        }
+    }
+
+    // Where the user placed the art, and the print area they placed it in
+    //
+    fn placed(&self, art_id: ArtEnum) -> Option<(PrintArea, ArtPlacement)> {
+        match art_id {
+            ArtEnum::Artwork0 => self.placement_0,
+            ArtEnum::Artwork1 => self.placement_1,
+            ArtEnum::Artwork2 => self.placement_2,
+            ArtEnum::BackArtwork => self.back_placement,
+        }
+    }
+
+    /// The rectangle around the art's non-transparent pixels, in pixels of the art
+    pub fn get_content(&self, art_id: ArtEnum) -> egui::Rect {
+        match art_id {
+            ArtEnum::Artwork0 => self.content_0,
+            ArtEnum::Artwork1 => self.content_1,
+            ArtEnum::Artwork2 => self.content_2,
+            ArtEnum::BackArtwork => self.back_content,
+        }
+    }
+
+    /// Where the art is placed in the print area.  Art that hasn't been placed, or was
+    /// placed in a different print area, fills the print area with its content.
+    pub fn get_placement(&self, art_id: ArtEnum, print_area: PrintArea) -> ArtPlacement {
+        self.get_user_placement(art_id, print_area)
+            .unwrap_or_else(|| {
+                let art_size = self.get_art(art_id).size();
+                ArtPlacement::fit_content(art_size, self.get_content(art_id), print_area)
+            })
+    }
+
+    /// Place the art in the print area.  None puts it back to filling the print area.
+    pub fn set_placement(
+        &mut self,
+        art_id: ArtEnum,
+        print_area: PrintArea,
+        placement: Option<ArtPlacement>,
+    ) {
+        let placement = placement.map(|placement| (print_area, placement));
+        match art_id {
+            ArtEnum::Artwork0 => self.placement_0 = placement,
+            ArtEnum::Artwork1 => self.placement_1 = placement,
+            ArtEnum::Artwork2 => self.placement_2 = placement,
+            ArtEnum::BackArtwork => self.back_placement = placement,
         }
     }
 
+    /// Replace the art with art the user just loaded.  Different art starts out
+    /// filling the print area.
+    pub fn set_new_art(
+        &mut self,
+        art_id: ArtEnum,
+        image: LoadedImage,
+        dependent_data: Option<ArtworkDependentData>,
+    ) {
+        self.set_placement(art_id, PrintArea::default(), None);
+        self.set_art(art_id, image, dependent_data);
+    }
+
+    /// Replace the art, i.e., with a fixed version of it or with its dependent data.
+    /// The art stays where the user placed it.
     pub fn set_art(
         &mut self,
         art_id: ArtEnum,
         image: LoadedImage,
         dependent_data: Option<ArtworkDependentData>,
     ) {
+        // Cropping the margins is the only fix that changes the art's size.  Move the
+        // placement with the crop, so the content prints where it did.
+        let old_size = self.get_art(art_id).size();
+        if image.size() != old_size {
+            if let Some((print_area, placement)) = self.placed(art_id) {
+                let cropped = placement.crop(old_size, self.get_content(art_id));
+                self.set_placement(art_id, print_area, Some(cropped));
+            }
+        }
+        let content = match &dependent_data {
+            Some(dd) => dd.margin_check().content(),
+            None => MarginCheck::new(&image).content(),
+        };
         match art_id {
             ArtEnum::Artwork0 => {
                 self.artwork_0 = image;
                 self.art_dependent_data_0 = dependent_data;
+                self.content_0 = content;
             }
             ArtEnum::Artwork1 => {
                 self.artwork_1 = image;
                 self.art_dependent_data_1 = dependent_data;
+                self.content_1 = content;
             }
             ArtEnum::Artwork2 => {
                 self.artwork_2 = image;
                 self.art_dependent_data_2 = dependent_data;
+                self.content_2 = content;
+            }
+            ArtEnum::BackArtwork => {
+                self.back_artwork = image;
+                self.back_art_dependent_data = dependent_data;
+                self.back_content = content;
             }
         }
     }
 }
+
+#[cfg(test)]
+mod art_storage_should {
+    use super::*;
+
+    #[test]
+    fn keep_the_art_in_place_while_its_dependent_data_comes_and_goes() {
+        // A square of ink in the middle of a wide transparent margin
+        let art = load_image_from_function(
+            [100, 100],
+            |x, y| {
+                if (40..60).contains(&x) && (40..60).contains(&y) {
+                    egui::Color32::BLACK
+                } else {
+                    egui::Color32::TRANSPARENT
+                }
+            },
+            "square",
+        );
+        let settings = ReportSettings::default();
+        let dependent_data = async_std::task::block_on(ArtworkDependentData::new(
+            &art,
+            &settings,
+            ShirtSide::Front,
+            None,
+        ));
+
+        let mut art_storage = ArtStorage::new();
+        art_storage.set_new_art(ArtEnum::Artwork1, art.clone(), None);
+        let placed = art_storage.get_placement(ArtEnum::Artwork1, PrintArea::FullFront);
+        assert_ne!(placed, ArtPlacement::fit(art.size(), PrintArea::FullFront));
+
+        art_storage.set_art(ArtEnum::Artwork1, art, Some(dependent_data));
+        assert_eq!(
+            placed,
+            art_storage.get_placement(ArtEnum::Artwork1, PrintArea::FullFront)
+        );
+        art_storage.forget_dependent_data();
+        assert_eq!(
+            placed,
+            art_storage.get_placement(ArtEnum::Artwork1, PrintArea::FullFront)
+        );
+    }
+
+    #[test]
+    fn put_new_art_back_to_filling_the_print_area() {
+        let solid =
+            |color: egui::Color32| load_image_from_function([100, 100], move |_, _| color, "solid");
+        let fit = ArtPlacement::fit(egui::vec2(100.0, 100.0), PrintArea::FullFront);
+        let mut placed = fit;
+        placed.width /= 2.0;
+        placed.rotation = 45.0;
+
+        let mut art_storage = ArtStorage::new();
+        art_storage.set_new_art(ArtEnum::Artwork0, solid(egui::Color32::RED), None);
+        art_storage.set_placement(ArtEnum::Artwork0, PrintArea::FullFront, Some(placed));
+
+        // A fix keeps the art where the user put it...
+        art_storage.set_art(ArtEnum::Artwork0, solid(egui::Color32::BLUE), None);
+        assert_eq!(
+            placed,
+            art_storage.get_placement(ArtEnum::Artwork0, PrintArea::FullFront)
+        );
+        // ...but new art the same size doesn't inherit it.
+        art_storage.set_new_art(ArtEnum::Artwork0, solid(egui::Color32::GREEN), None);
+        assert_eq!(
+            fit,
+            art_storage.get_placement(ArtEnum::Artwork0, PrintArea::FullFront)
+        );
+    }
+
+    #[test]
+    fn keep_cropped_art_where_the_user_put_it() {
+        // A square of ink in the top left of a wide transparent margin
+        let art = load_image_from_function(
+            [100, 100],
+            |x, y| {
+                if (10..30).contains(&x) && (20..40).contains(&y) {
+                    egui::Color32::BLACK
+                } else {
+                    egui::Color32::TRANSPARENT
+                }
+            },
+            "square",
+        );
+        let placed = ArtPlacement {
+            center: egui::Vec2::new(6.0, 7.0),
+            width: 10.0,
+            rotation: 90.0,
+        };
+        let mut art_storage = ArtStorage::new();
+        art_storage.set_new_art(ArtEnum::Artwork2, art.clone(), None);
+        art_storage.set_placement(ArtEnum::Artwork2, PrintArea::FullFront, Some(placed));
+        let square_corner = placed.art_to_art_space(art.size()) * nalgebra::vector![0.1, 0.2, 1.0];
+
+        let cropped = crop_margins(&art, "cropped");
+        art_storage.set_art(ArtEnum::Artwork2, cropped.clone(), None);
+        let placement = art_storage.get_placement(ArtEnum::Artwork2, PrintArea::FullFront);
+        let cropped_corner =
+            placement.art_to_art_space(cropped.size()) * nalgebra::vector![0.0, 0.0, 1.0];
+        assert!((square_corner - cropped_corner).norm() < 1e-4);
+    }
+}
@@ -1,11 +1,23 @@
+use crate::art_placement::*;
 use crate::artwork::*;
+use crate::background::*;
 use crate::error::*;
+use crate::fringe::*;
+use crate::garment::*;
+use crate::halftone::*;
 use crate::image_utils::*;
+use crate::knockout::*;
 use crate::loaded_image::*;
+use crate::margins::*;
+use crate::report_html::*;
+use crate::report_settings::*;
+use crate::shirt_colors::*;
+use crate::specks::*;
+use crate::tshirt_storage::*;
 use std::future::Future;
 
 // Concurrent pipe and payload definition for asyncronous jobs
-pub type AsyncImageLoadResult = Result<AsyncImageLoadPayload, Error>;
+pub type AsyncImageLoadResult = Result<AsyncPayload, Error>;
 pub type AsyncImageSender = std::sync::mpsc::Sender<AsyncImageLoadResult>;
 pub type AsyncImageReceiver = std::sync::mpsc::Receiver<AsyncImageLoadResult>;
 
@@ -18,11 +30,24 @@ pub type AsyncImageReceiver = std::sync::mpsc::Receiver<AsyncImageLoadResult>;
 //                  doesn't have to compute the dependent data.  For example, it may want
 //                  to send the core artwork first, so it's visible quickly, and then
 //                  compute dependent data later.
+// new_art        - True for art the user just loaded, which starts out filling the
+//                  print area.  Fixed art keeps the placement the user gave it.
 //
 pub struct AsyncImageLoadPayload {
     pub art_id: ArtEnum,
     pub art: LoadedImage,
     pub dependent_data: Option<ArtworkDependentData>,
+    pub new_art: bool,
+}
+
+//
+// Everything an asyncronous job can send to the main app
+//
+pub enum AsyncPayload {
+    Art(Box<AsyncImageLoadPayload>),
+    ShirtCatalog(ShirtColorCatalog),
+    TShirt(ShirtDye, Box<TShirtImages>),
+    Garment(Box<Garment>),
 }
 
 //
@@ -40,20 +65,40 @@ fn app_execute<F: Future<Output = ()> + 'static>(f: F) {
     wasm_bindgen_futures::spawn_local(f);
 }
 
+// What app_execute needs a task's parts to be.  They're sent to another thread,
+// except in web-assembly.
+//
+#[cfg(not(target_arch = "wasm32"))]
+trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send> MaybeSend for T {}
+#[cfg(target_arch = "wasm32")]
+trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T> MaybeSend for T {}
+
+//
+// Give other tasks a chance to run.  The artwork analysis code calls this between
+// expensive steps.  It doesn't know about the GUI, so it can't ask for a repaint.
+//
+pub async fn yield_now() {
+    let one_milli = std::time::Duration::from_millis(1);
+    async_std::task::sleep(one_milli).await;
+}
+
 //
 // Explicite context switch.  This also sends a repaint request to the main GUI so
 // load animations get repainted.
 //
 pub async fn context_switch(ctx: &egui::Context) {
     ctx.request_repaint();
-    let one_milli = std::time::Duration::from_millis(1);
-    async_std::task::sleep(one_milli).await;
+    yield_now().await;
 }
 
 //
 // Asyncronous file load using the rfd library
 //
-pub async fn load_image(ctx: &egui::Context) -> Result<LoadedImage, Error> {
+pub async fn load_image() -> Result<LoadedImage, Error> {
     let file = rfd::AsyncFileDialog::new()
         .add_filter("All", &["png", "jpg", "jpeg", "jpe", "jif", "jtif", "svg"])
         .add_filter("Png Images", &["png"])
@@ -69,7 +114,7 @@ pub async fn load_image(ctx: &egui::Context) -> Result<LoadedImage, Error> {
         ));
     }
     let data: Vec<u8> = file.unwrap().read().await;
-    let image = load_image_from_untrusted_source(&data, "loaded_data", ctx)?;
+    let image = load_image_from_untrusted_source(&data, "loaded_data")?;
     Ok(image)
 }
 
@@ -81,9 +126,11 @@ pub async fn load_image(ctx: &egui::Context) -> Result<LoadedImage, Error> {
 pub fn do_load(
     main_thread_ctx: &egui::Context,
     art_id: ArtEnum,
+    main_thread_settings: &ReportSettings,
     main_thread_sender: &AsyncImageSender,
 ) {
     let ctx = main_thread_ctx.clone();
+    let settings = main_thread_settings.clone();
     let sender = main_thread_sender.clone();
 
     // Execute the load asyncronously so we don't block the main thread.
@@ -96,7 +143,7 @@ pub fn do_load(
     app_execute(async move {
         // 1.  Load the image from the user.  Handle any failures
         //
-        let art_maybe = load_image(&ctx).await;
+        let art_maybe = load_image().await;
         if art_maybe.is_err() {
             sender.send(Err(art_maybe.err().unwrap())).unwrap();
             ctx.request_repaint();
@@ -107,68 +154,281 @@ pub fn do_load(
         // 2.  Send the result of that load to the main app so the user sees it quickly
         //
         context_switch(&ctx).await;
-        let send_image = Ok(AsyncImageLoadPayload {
+        let send_image = Ok(AsyncPayload::Art(Box::new(AsyncImageLoadPayload {
             art_id,
             art: art.clone(),
             dependent_data: None,
-        });
+            new_art: true,
+        })));
         sender.send(send_image).unwrap();
 
         // 3.  Compute dependent data for the art we just loaded
         //
         context_switch(&ctx).await;
-        let dependent_data = ArtworkDependentData::new(&ctx, &art).await;
+        let dependent_data = ArtworkDependentData::new(&art, &settings, art_id.side(), None).await;
 
         // 4.  Send the artwork and the dependent data to the main app
         //
-        let send_image_and_dep_data = Ok(AsyncImageLoadPayload {
+        let send_image_and_dep_data = Ok(AsyncPayload::Art(Box::new(AsyncImageLoadPayload {
             art_id,
             art,
             dependent_data: Some(dependent_data),
-        });
+            new_art: false,
+        })));
         sender.send(send_image_and_dep_data).unwrap();
         context_switch(&ctx).await;
     });
 }
 
 //
-// A utility to fix partial transparency problems.
-//
-// Schedules an asyncronous task to modify the artwork and update the dependent
-// data.
+// The common part of every fix.  Schedules an asyncronous task that runs fix on a
+// copy of the art, computes the fixed art's dependent data, and sends them both to the
+// main app.  fix gets the art and the settings, and may yield while it works.
 //
-pub fn partialt_fix(
+fn spawn_art_fix<F, Fut>(
     main_thread_ctx: &egui::Context,
     main_thread_art: &LoadedImage,
     art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    main_thread_settings: &ReportSettings,
     main_thread_sender: &AsyncImageSender,
-) {
+    fix: F,
+) where
+    F: FnOnce(LoadedImage, ReportSettings) -> Fut + MaybeSend + 'static,
+    Fut: Future<Output = LoadedImage> + MaybeSend,
+{
     //
     // Clone data because we're going to do the heavy listing asyncronously
     //
     let orig_art = main_thread_art.clone();
     let ctx = main_thread_ctx.clone();
+    let settings = main_thread_settings.clone();
     let sender = main_thread_sender.clone();
 
     app_execute(async move {
-        let art = load_image_from_existing_image(
-            &orig_art,
-            &correct_alpha_for_tshirt,
-            "blah_blah_fixed_art", // todo, better name...
-            &ctx,
-        );
+        let art = fix(orig_art, settings.clone()).await;
         context_switch(&ctx).await;
-        let dependent_data = ArtworkDependentData::new(&ctx, &art).await;
-        let image_to_send = Ok(AsyncImageLoadPayload {
+        let dependent_data =
+            ArtworkDependentData::new(&art, &settings, art_id.side(), placement).await;
+        let image_to_send = Ok(AsyncPayload::Art(Box::new(AsyncImageLoadPayload {
             art_id,
             art,
             dependent_data: Some(dependent_data),
-        });
+            new_art: false,
+        })));
         sender.send(image_to_send).unwrap();
         context_switch(&ctx).await;
     });
 }
 
+//
+// A utility to fix partial transparency problems.
+//
+pub fn partialt_fix(
+    ctx: &egui::Context,
+    art: &LoadedImage,
+    art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    settings: &ReportSettings,
+    sender: &AsyncImageSender,
+) {
+    spawn_art_fix(
+        ctx,
+        art,
+        art_id,
+        placement,
+        settings,
+        sender,
+        |art, settings| async move {
+            correct_alpha(
+                &art,
+                &settings.alpha_fix,
+                "blah_blah_fixed_art", // todo, better name...
+            )
+        },
+    );
+}
+
+//
+// Crop the transparent margin off the art
+//
+pub fn crop_margins_fix(
+    ctx: &egui::Context,
+    art: &LoadedImage,
+    art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    settings: &ReportSettings,
+    sender: &AsyncImageSender,
+) {
+    spawn_art_fix(
+        ctx,
+        art,
+        art_id,
+        placement,
+        settings,
+        sender,
+        |art, _| async move { crop_margins(&art, "cropped_art") },
+    );
+}
+
+//
+// Take the matte color back out of the art's soft edges, then fix the partial
+// transparency, so the edges don't print with a halo.
+//
+pub fn defringe_fix(
+    ctx: &egui::Context,
+    art: &LoadedImage,
+    art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    matte: egui::Color32,
+    settings: &ReportSettings,
+    sender: &AsyncImageSender,
+) {
+    spawn_art_fix(
+        ctx,
+        art,
+        art_id,
+        placement,
+        settings,
+        sender,
+        move |art, settings| async move {
+            let defringed = defringe(&art, matte, "defringed_art");
+            yield_now().await;
+            correct_alpha(&defringed, &settings.alpha_fix, "defringed_art")
+        },
+    );
+}
+
+//
+// Thicken the art's lines that are too thin to print, min_pixels being the thinnest
+// line that will print.
+//
+pub fn thin_line_fix(
+    ctx: &egui::Context,
+    art: &LoadedImage,
+    art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    min_pixels: usize,
+    settings: &ReportSettings,
+    sender: &AsyncImageSender,
+) {
+    spawn_art_fix(
+        ctx,
+        art,
+        art_id,
+        placement,
+        settings,
+        sender,
+        move |art, _| async move { thicken_thin_lines(&art, min_pixels, "thickened_art").await },
+    );
+}
+
+//
+// Make the solid background around the art transparent.
+//
+pub fn background_fix(
+    ctx: &egui::Context,
+    art: &LoadedImage,
+    art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    settings: &ReportSettings,
+    sender: &AsyncImageSender,
+) {
+    spawn_art_fix(
+        ctx,
+        art,
+        art_id,
+        placement,
+        settings,
+        sender,
+        |art, settings| async move {
+            remove_background(
+                &art,
+                settings.background_tolerance,
+                "background_removed_art",
+            )
+        },
+    );
+}
+
+//
+// Make the parts of the art that are the shirt's color transparent, so the shirt
+// shows through.
+//
+pub fn knockout_fix(
+    ctx: &egui::Context,
+    art: &LoadedImage,
+    art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    shirt_color: egui::Color32,
+    settings: &ReportSettings,
+    sender: &AsyncImageSender,
+) {
+    spawn_art_fix(
+        ctx,
+        art,
+        art_id,
+        placement,
+        settings,
+        sender,
+        move |art, settings| async move {
+            knock_out(
+                &art,
+                shirt_color,
+                settings.knockout_tolerance,
+                "knocked_out_art",
+            )
+        },
+    );
+}
+
+//
+// Erase the islands of ink smaller than max_pixels.
+//
+pub fn speck_fix(
+    ctx: &egui::Context,
+    art: &LoadedImage,
+    art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    max_pixels: usize,
+    settings: &ReportSettings,
+    sender: &AsyncImageSender,
+) {
+    spawn_art_fix(
+        ctx,
+        art,
+        art_id,
+        placement,
+        settings,
+        sender,
+        move |art, _| async move { remove_specks(&art, max_pixels, "despecked_art") },
+    );
+}
+
+//
+// Replace the art's partial transparency with halftone dots, sized for the resolution
+// the art is printed at.
+//
+pub fn halftone_fix(
+    ctx: &egui::Context,
+    art: &LoadedImage,
+    art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    dpi: f32,
+    settings: &ReportSettings,
+    sender: &AsyncImageSender,
+) {
+    spawn_art_fix(
+        ctx,
+        art,
+        art_id,
+        placement,
+        settings,
+        sender,
+        move |art, settings| async move { halftone(&art, dpi, &settings.halftone, "halftoned_art") },
+    );
+}
+
 //
 // Compute art dependent data asyncronously, then send it to the main thread
 //
@@ -176,20 +436,196 @@ pub fn cache_in_dependent_data(
     main_thread_ctx: &egui::Context,
     main_thread_art: &LoadedImage,
     art_id: ArtEnum,
+    placement: Option<ArtPlacement>,
+    main_thread_settings: &ReportSettings,
     main_thread_sender: &AsyncImageSender,
 ) {
     let art = main_thread_art.clone();
     let ctx = main_thread_ctx.clone();
+    let settings = main_thread_settings.clone();
     let sender = main_thread_sender.clone();
 
     app_execute(async move {
-        let dependent_data = ArtworkDependentData::new(&ctx, &art).await;
-        let image_to_send = Ok(AsyncImageLoadPayload {
+        let dependent_data =
+            ArtworkDependentData::new(&art, &settings, art_id.side(), placement).await;
+        let image_to_send = Ok(AsyncPayload::Art(Box::new(AsyncImageLoadPayload {
             art_id,
             art,
             dependent_data: Some(dependent_data),
-        });
+            new_art: false,
+        })));
         sender.send(image_to_send).unwrap();
         context_switch(&ctx).await;
     });
 }
+
+//
+// Load a shirt color catalog from a JSON file
+//
+pub fn load_shirt_catalog(main_thread_ctx: &egui::Context, main_thread_sender: &AsyncImageSender) {
+    let ctx = main_thread_ctx.clone();
+    let sender = main_thread_sender.clone();
+
+    app_execute(async move {
+        let file = rfd::AsyncFileDialog::new()
+            .add_filter("Shirt Color Catalog", &["json"])
+            .pick_file()
+            .await;
+        let result = match file {
+            None => Err(Error::new(
+                ErrorTypes::FileImportAborted,
+                "Catalog import cancelled by user",
+            )),
+            Some(file) => {
+                let data = file.read().await;
+                ShirtColorCatalog::from_json(&String::from_utf8_lossy(&data))
+                    .map(AsyncPayload::ShirtCatalog)
+            }
+        };
+        sender.send(result).unwrap();
+        context_switch(&ctx).await;
+    });
+}
+
+//
+// Recolor the blue t-shirt for each dye, one t-shirt at a time, sending each to the
+// main app as it's made.
+//
+pub fn recolor_tshirts(
+    main_thread_ctx: &egui::Context,
+    main_thread_blue_shirt: &TShirtImages,
+    dyes: Vec<ShirtDye>,
+    main_thread_sender: &AsyncImageSender,
+) {
+    let ctx = main_thread_ctx.clone();
+    let blue_shirt = main_thread_blue_shirt.clone();
+    let sender = main_thread_sender.clone();
+
+    app_execute(async move {
+        for dye in dyes {
+            let images = TShirtImages::recolor(&blue_shirt, dye);
+            sender
+                .send(Ok(AsyncPayload::TShirt(dye, Box::new(images))))
+                .unwrap();
+            context_switch(&ctx).await;
+        }
+    });
+}
+
+//
+// Import a garment mockup photo.  The garment is named after the file.
+//
+pub fn load_garment(main_thread_ctx: &egui::Context, main_thread_sender: &AsyncImageSender) {
+    let ctx = main_thread_ctx.clone();
+    let sender = main_thread_sender.clone();
+
+    app_execute(async move {
+        let file = rfd::AsyncFileDialog::new()
+            .add_filter("All", &["png", "jpg", "jpeg", "jpe", "jif", "jtif"])
+            .add_filter("Png Images", &["png"])
+            .add_filter("Jpeg Images", &["jpg", "jpeg", "jpe", "jif", "jtif"])
+            .pick_file()
+            .await;
+        let result = match file {
+            None => Err(Error::new(
+                ErrorTypes::FileImportAborted,
+                "Garment import cancelled by user",
+            )),
+            Some(file) => {
+                let file_name = file.file_name();
+                let name = file_name
+                    .rsplit_once('.')
+                    .map_or(file_name.as_str(), |(stem, _)| stem);
+                let data = file.read().await;
+                Garment::from_image_file(name, data)
+                    .map(|garment| AsyncPayload::Garment(Box::new(garment)))
+            }
+        };
+        sender.send(result).unwrap();
+        context_switch(&ctx).await;
+    });
+}
+
+//
+// Asyncronous file save using the rfd library.  Web assembly builds download the file.
+//
+// Cancelling the save isn't an error - there's just nothing to do.
+//
+async fn save_file(contents: &[u8], file_name: &str, extension: &str) -> Result<(), Error> {
+    let file = rfd::AsyncFileDialog::new()
+        .add_filter(extension.to_uppercase(), &[extension])
+        .set_file_name(format!("{}.{}", file_name, extension))
+        .save_file()
+        .await;
+
+    match file {
+        None => Ok(()),
+        Some(file) => file.write(contents).await.map_err(|e| {
+            Error::new(
+                ErrorTypes::FileExportError,
+                format!("Saving {}.{} failed: {}", file_name, extension, e),
+            )
+        }),
+    }
+}
+
+//
+// Save the JSON report
+//
+pub fn save_report(
+    main_thread_ctx: &egui::Context,
+    report: String,
+    main_thread_sender: &AsyncImageSender,
+) {
+    let ctx = main_thread_ctx.clone();
+    let sender = main_thread_sender.clone();
+
+    app_execute(async move {
+        if let Err(e) = save_file(report.as_bytes(), "tshirt_report", "json").await {
+            sender.send(Err(e)).unwrap();
+        }
+        context_switch(&ctx).await;
+    });
+}
+
+//
+// Save an image as a .png file.  Encoding is slow for big images, so it's done
+// asyncronously.
+//
+pub fn save_png(
+    main_thread_ctx: &egui::Context,
+    image: LoadedImage,
+    file_name: &'static str,
+    main_thread_sender: &AsyncImageSender,
+) {
+    let ctx = main_thread_ctx.clone();
+    let sender = main_thread_sender.clone();
+
+    app_execute(async move {
+        if let Err(e) = save_file(&image.encode_png(), file_name, "png").await {
+            sender.send(Err(e)).unwrap();
+        }
+        context_switch(&ctx).await;
+    });
+}
+
+//
+// Save the HTML report.  Encoding the images for the report is slow, so it's done
+// asyncronously too.
+//
+pub fn save_html_report(
+    main_thread_ctx: &egui::Context,
+    report: HtmlReport,
+    main_thread_sender: &AsyncImageSender,
+) {
+    let ctx = main_thread_ctx.clone();
+    let sender = main_thread_sender.clone();
+
+    app_execute(async move {
+        let html = report.to_html();
+        if let Err(e) = save_file(html.as_bytes(), "tshirt_report", "html").await {
+            sender.send(Err(e)).unwrap();
+        }
+        context_switch(&ctx).await;
+    });
+}
@@ -0,0 +1,351 @@
+//! Background removal
+//!
+//! A lot of art arrives as a JPEG on a white (or nearly white) box.  JPEGs have no
+//! transparency, so the box gets printed too - an opaque rectangle that sends the Bib
+//! score through the roof.  The background is found by flood filling in from the
+//! border of the image, so a white area inside the art (i.e., the whites of an eye)
+//! isn't touched.
+//!
+//! Anti-aliasing leaves a fringe of pixels along the edge of the art that are part
+//! background, part art.  Left alone they print as a pale halo, so the background's
+//! share is taken back out of them.
+
+use crate::image_utils::*;
+use crate::loaded_image::*;
+use std::collections::HashMap;
+
+// How far from the background the fringe is cleaned, in pixels
+const FRINGE_WIDTH: usize = 2;
+// Fringe pixels with less of the art than this in them are dropped
+const MIN_FRINGE_COVERAGE: f32 = 0.5;
+// How close to the background color a pixel has to be for the Background report to
+// count it as background
+const DETECTION_TOLERANCE: u32 = 24;
+// The Background report's tool paints the background this color
+const BACKGROUND_FLAG: egui::Color32 = egui::Color32::from_rgb(255, 0, 255);
+
+fn border_indices(width: usize, height: usize) -> impl Iterator<Item = usize> {
+    let top_and_bottom = (0..width).flat_map(move |x| [x, x + (height - 1) * width]);
+    let sides = (0..height).flat_map(move |y| [y * width, y * width + width - 1]);
+    top_and_bottom.chain(sides)
+}
+
+/// The most common opaque color along the border of the art, if there is one
+pub fn background_color(art: &LoadedImage) -> Option<egui::Color32> {
+    let [width, height] = *art.size_as_array();
+    let pixels = art.pixels();
+    // Bucket colors, so JPEG noise doesn't split up the background.
+    let mut buckets: HashMap<[u8; 3], (u32, [u32; 3])> = HashMap::new();
+    for index in border_indices(width, height) {
+        let pixel = pixels[index];
+        if pixel.a() != 255 {
+            continue;
+        }
+        let key = [pixel.r() >> 4, pixel.g() >> 4, pixel.b() >> 4];
+        let (count, sum) = buckets.entry(key).or_insert((0, [0; 3]));
+        *count += 1;
+        sum[0] += pixel.r() as u32;
+        sum[1] += pixel.g() as u32;
+        sum[2] += pixel.b() as u32;
+    }
+    let (count, sum) = buckets.into_values().max_by_key(|(count, _)| *count)?;
+    let average = |sum: u32| (sum / count) as u8;
+    Some(egui::Color32::from_rgb(
+        average(sum[0]),
+        average(sum[1]),
+        average(sum[2]),
+    ))
+}
+
+// How much of a pixel is art rather than background, treating the pixel as the art
+// blended over the background, and the art's color with the background taken out.
+//
+fn unmix(pixel: egui::Color32, background: egui::Color32) -> (f32, egui::Color32) {
+    let channel_coverage = |p: u8, b: u8| {
+        let (p, b) = (p as f32, b as f32);
+        if p > b {
+            (p - b) / (255.0 - b)
+        } else if p < b {
+            (b - p) / b
+        } else {
+            0.0
+        }
+    };
+    let coverage = channel_coverage(pixel.r(), background.r())
+        .max(channel_coverage(pixel.g(), background.g()))
+        .max(channel_coverage(pixel.b(), background.b()));
+    if coverage <= 0.0 {
+        return (0.0, background);
+    }
+    let channel = |p: u8, b: u8| {
+        let (p, b) = (p as f32, b as f32);
+        ((p - b) / coverage + b).round().clamp(0.0, 255.0) as u8
+    };
+    let color = egui::Color32::from_rgb(
+        channel(pixel.r(), background.r()),
+        channel(pixel.g(), background.g()),
+        channel(pixel.b(), background.b()),
+    );
+    (coverage, color)
+}
+
+// Flood fill the background in from the border.  Pixels within tolerance of the
+// background color that connect to the border are background.
+//
+fn flood_background(art: &LoadedImage, background: egui::Color32, tolerance: u32) -> Vec<bool> {
+    let [width, height] = *art.size_as_array();
+    let pixels = art.pixels();
+    let matches =
+        |pixel: egui::Color32| pixel.a() == 255 && color_distance(pixel, background) <= tolerance;
+    let mut is_background = vec![false; width * height];
+    let mut stack: Vec<usize> = border_indices(width, height)
+        .filter(|index| matches(pixels[*index]))
+        .collect();
+    while let Some(index) = stack.pop() {
+        if is_background[index] {
+            continue;
+        }
+        is_background[index] = true;
+        let (x, y) = (index % width, index / width);
+        let neighbours = [
+            (x > 0).then(|| index - 1),
+            (x + 1 < width).then(|| index + 1),
+            (y > 0).then(|| index - width),
+            (y + 1 < height).then(|| index + width),
+        ];
+        for neighbour in neighbours.into_iter().flatten() {
+            if !is_background[neighbour] && matches(pixels[neighbour]) {
+                stack.push(neighbour);
+            }
+        }
+    }
+    is_background
+}
+
+/// The art with the background around it made transparent.  Pixels within tolerance
+/// (0 to 255, per color channel) of the background color that connect to the border
+/// of the image are background.
+pub fn remove_background(
+    art: &LoadedImage,
+    tolerance: u32,
+    name: impl Into<String>,
+) -> LoadedImage {
+    let Some(background) = background_color(art) else {
+        return load_image_from_existing_size(art, art.pixels().clone(), name);
+    };
+    let [width, height] = *art.size_as_array();
+    let mut pixels = art.pixels().clone();
+    let is_background = flood_background(art, background, tolerance);
+
+    // Find the fringe - the pixels within FRINGE_WIDTH of the background.
+    let mut distance = vec![usize::MAX; width * height];
+    let mut edge: Vec<usize> = (0..width * height).filter(|i| is_background[*i]).collect();
+    edge.iter().for_each(|i| distance[*i] = 0);
+    for step in 1..=FRINGE_WIDTH {
+        let mut next_edge = Vec::new();
+        for index in edge {
+            let (x, y) = (index % width, index / width);
+            for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+                for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                    let neighbour = nx + ny * width;
+                    if distance[neighbour] == usize::MAX {
+                        distance[neighbour] = step;
+                        next_edge.push(neighbour);
+                    }
+                }
+            }
+        }
+        edge = next_edge;
+    }
+
+    // The color of the pixel within FRINGE_WIDTH of the pixel at index that's furthest
+    // from the background, if it's further in than the pixel at index.  Past the
+    // fringe is the art's own color.
+    let inside = |index: usize| {
+        let (x, y) = (index % width, index / width);
+        (y.saturating_sub(FRINGE_WIDTH)..=(y + FRINGE_WIDTH).min(height - 1))
+            .flat_map(|ny| {
+                (x.saturating_sub(FRINGE_WIDTH)..=(x + FRINGE_WIDTH).min(width - 1))
+                    .map(move |nx| nx + ny * width)
+            })
+            .filter(|near| distance[*near] > distance[index])
+            .max_by_key(|near| distance[*near])
+            .map(|near| art.pixels()[near])
+    };
+
+    for (index, pixel) in pixels.iter_mut().enumerate() {
+        // Only pixels that are a blend of the background and a different color further
+        // in are fringe.  The edge of solid art (i.e., pale art on a white box) is the
+        // same color as its inside, and is left alone.
+        let is_blend =
+            || inside(index).is_some_and(|inside| color_distance(*pixel, inside) > tolerance);
+        if is_background[index] {
+            *pixel = egui::Color32::TRANSPARENT;
+        } else if distance[index] != usize::MAX && pixel.a() == 255 && is_blend() {
+            let (coverage, color) = unmix(*pixel, background);
+            *pixel = if coverage < MIN_FRINGE_COVERAGE {
+                egui::Color32::TRANSPARENT
+            } else {
+                color
+            };
+        }
+    }
+    load_image_from_existing_size(art, pixels, name)
+}
+
+/// Did the art come with its background?  If the border of the art is opaque and
+/// nearly all one color, it probably did.
+pub struct BackgroundCheck {
+    border_percent: u32,
+    flagged: LoadedImage,
+    art_bounds: Option<egui::Rect>,
+}
+
+impl BackgroundCheck {
+    pub fn new(art: &LoadedImage) -> Self {
+        let [width, height] = *art.size_as_array();
+        let background = background_color(art);
+        let is_background = match background {
+            Some(background) => flood_background(art, background, DETECTION_TOLERANCE),
+            None => vec![false; width * height],
+        };
+
+        let border = border_indices(width, height).count();
+        let on_border = border_indices(width, height)
+            .filter(|index| is_background[*index])
+            .count();
+
+        // The background surrounds the art, so its own bounds are the whole image.  The
+        // edge worth showing is where the background stops and the art starts.
+        let mut art_bounds: Option<egui::Rect> = None;
+        if is_background.contains(&true) {
+            for (index, _) in is_background.iter().enumerate().filter(|(_, b)| !**b) {
+                let pixel = egui::Rect::from_min_size(
+                    egui::pos2((index % width) as f32, (index / width) as f32),
+                    egui::vec2(1.0, 1.0),
+                );
+                art_bounds = Some(art_bounds.map_or(pixel, |bounds| bounds.union(pixel)));
+            }
+        }
+
+        let pixels = art
+            .pixels()
+            .iter()
+            .zip(&is_background)
+            .map(|(pixel, background)| if *background { BACKGROUND_FLAG } else { *pixel })
+            .collect();
+        Self {
+            border_percent: (100 * on_border / border.max(1)) as u32,
+            flagged: load_image_from_existing_size(art, pixels, "background_flagged"),
+            art_bounds,
+        }
+    }
+
+    /// Percentage of the border of the art that's opaque background
+    pub fn border_percent(&self) -> u32 {
+        self.border_percent
+    }
+
+    /// The art with the background painted a bright color
+    pub fn flagged(&self) -> &LoadedImage {
+        &self.flagged
+    }
+
+    /// The rectangle around the art inside the background, in pixels of the art.  None
+    /// if there's no background.
+    pub fn art_bounds(&self) -> Option<egui::Rect> {
+        self.art_bounds
+    }
+}
+
+#[cfg(test)]
+mod background_should {
+    use super::*;
+
+    const OFF_WHITE: egui::Color32 = egui::Color32::from_rgb(250, 252, 249);
+
+    // A red ring around a white hole, on an off white box.  The ring's outside edge is
+    // anti-aliased into the background.
+    fn ring_on_a_box() -> LoadedImage {
+        let ring_on_a_box = |x: usize, y: usize| {
+            let (x, y) = (x as f32 - 19.5, y as f32 - 19.5);
+            let radius = (x * x + y * y).sqrt();
+            if radius < 6.0 {
+                egui::Color32::WHITE
+            } else if radius < 12.0 {
+                egui::Color32::RED
+            } else if radius < 13.0 {
+                // Half red, half background
+                egui::Color32::from_rgb(252, 126, 124)
+            } else if radius < 14.0 {
+                // Mostly background
+                egui::Color32::from_rgb(251, 220, 218)
+            } else {
+                OFF_WHITE
+            }
+        };
+        load_image_from_function([40, 40], ring_on_a_box, "ring_on_a_box")
+    }
+
+    #[test]
+    fn find_the_background_color() {
+        assert_eq!(Some(OFF_WHITE), background_color(&ring_on_a_box()));
+        let transparent = load_image_from_pixels(vec![egui::Color32::TRANSPARENT; 4], [2, 2], "t");
+        assert_eq!(None, background_color(&transparent));
+    }
+
+    #[test]
+    fn remove_the_background_but_not_the_art() {
+        let fixed = remove_background(&ring_on_a_box(), 16, "fixed");
+        let at = |x: usize, y: usize| fixed.pixels()[x + y * 40];
+        assert_eq!(egui::Color32::TRANSPARENT, at(0, 0));
+        assert_eq!(egui::Color32::TRANSPARENT, at(39, 20));
+        assert_eq!(egui::Color32::RED, at(20, 11));
+        // The hole isn't connected to the border, so it stays
+        assert_eq!(egui::Color32::WHITE, at(20, 20));
+    }
+
+    #[test]
+    fn detect_art_that_came_with_its_background() {
+        let check = BackgroundCheck::new(&ring_on_a_box());
+        assert_eq!(100, check.border_percent());
+        // The ring, including its anti-aliased edge, is inside the background.
+        let ring = egui::Rect::from_min_max(egui::pos2(6.0, 6.0), egui::pos2(34.0, 34.0));
+        assert_eq!(Some(ring), check.art_bounds());
+        assert_eq!(BACKGROUND_FLAG, check.flagged().pixels()[0]);
+        assert_eq!(egui::Color32::WHITE, check.flagged().pixels()[20 + 20 * 40]);
+
+        let fixed = remove_background(&ring_on_a_box(), 16, "fixed");
+        let check = BackgroundCheck::new(&fixed);
+        assert_eq!(0, check.border_percent());
+        assert_eq!(None, check.art_bounds());
+    }
+
+    #[test]
+    fn clean_the_fringe() {
+        let fixed = remove_background(&ring_on_a_box(), 16, "fixed");
+        assert!(fixed.pixels().iter().all(|p| p.a() == 0 || p.a() == 255));
+        // The half red pixels are red again, and the mostly background ones are gone.
+        let pale = |p: &&egui::Color32| p.a() == 255 && p.g() > 100 && p.r() > 200 && p.g() < 240;
+        assert_eq!(0, fixed.pixels().iter().filter(pale).count());
+        let half_red = fixed.pixels()[20 + 7 * 40];
+        assert!(half_red.r() > 240 && half_red.g() < 10, "{:?}", half_red);
+    }
+
+    #[test]
+    fn keep_the_edge_of_pale_art() {
+        // A solid light gray square on a white box
+        let gray = egui::Color32::from_rgb(200, 200, 200);
+        let square_on_a_box = |x, y| match (x, y) {
+            (5..=14, 5..=14) => gray,
+            _ => egui::Color32::WHITE,
+        };
+        let art = load_image_from_function([20, 20], square_on_a_box, "square_on_a_box");
+        let fixed = remove_background(&art, 16, "fixed");
+        let at = |x: usize, y: usize| fixed.pixels()[x + y * 20];
+        assert_eq!(egui::Color32::TRANSPARENT, at(4, 10));
+        for (x, y) in [(5, 5), (5, 10), (14, 14), (10, 6)] {
+            assert_eq!(gray, at(x, y), "{}, {}", x, y);
+        }
+    }
+}
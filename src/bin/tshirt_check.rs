@@ -0,0 +1,185 @@
+//! Headless T-Shirt artwork checker
+//!
+//! Runs the same reports the T-Squared app shows in its right panel, but from the
+//! command line and without opening a window.  Useful for gating artwork submissions
+//! in scripts.
+//!
+//! Usage: tshirt_check [--json] [--back] [--embedded-size] [--shirt-color <#rrggbb>]
+//!                     [--settings <settings.json>] <image> [<image> ...]
+//!
+//! --json prints the reports as a JSON array (one entry per image) instead of a table.
+//! --settings reads the report thresholds from a JSON file.  Settings missing from the
+//! file keep their default values.
+//! --back checks the images as art for the back of the T-Shirt instead of the front.
+//! --embedded-size prints images at the size their embedded resolution says, instead
+//! of scaling them to fill the print area.  Images without one are still scaled.
+//! --shirt-color is the color of the shirt the contrast report checks against.  It
+//! defaults to the first color in the app's shirt color catalog.
+//!
+//! Exit status is 0 if every report passes or warns, 1 if any report fails, and 2 if
+//! the arguments were bad or an image couldn't be loaded.
+//!
+
+use tshirt_checker::*;
+
+const EXIT_FAIL: i32 = 1;
+const EXIT_ERROR: i32 = 2;
+
+// One image's reports, as printed by --json
+//
+#[derive(serde::Serialize)]
+struct FileReport {
+    file: String,
+    #[serde(flatten)]
+    report: ArtworkReport,
+}
+
+// Load one image and run every report on it.
+//
+fn check_artwork(
+    path: &str,
+    report_templates: &ReportTemplates,
+    settings: &ReportSettings,
+    side: ShirtSide,
+    embedded_size: bool,
+    shirt_color: egui::Color32,
+) -> Result<ArtworkReport, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    let art = load_image_from_untrusted_source(&bytes, path)
+        .map_err(|e| format!("{}: {}", path, e.msg()))?;
+    let print_area = settings.print_area_for(side);
+    let user_placement = match art.embedded_dpi() {
+        Some(dpi) if embedded_size => Some(ArtPlacement::at_dpi(art.size(), dpi, print_area)),
+        _ => None,
+    };
+    let dependent_data = async_std::task::block_on(ArtworkDependentData::new(
+        &art,
+        settings,
+        side,
+        user_placement,
+    ));
+    let placement = user_placement.unwrap_or_else(|| {
+        ArtPlacement::fit_content(
+            art.size(),
+            dependent_data.margin_check().content(),
+            print_area,
+        )
+    });
+    Ok(ArtworkReport::new(
+        &art,
+        &placement,
+        &dependent_data,
+        report_templates,
+        settings,
+        shirt_color,
+    ))
+}
+
+// Load the report settings from a JSON file.
+//
+fn load_settings(path: &str) -> Result<ReportSettings, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: tshirt_check [--json] [--back] [--embedded-size] [--shirt-color <#rrggbb>] [--settings <settings.json>] <image> [<image> ...]"
+    );
+    std::process::exit(EXIT_ERROR);
+}
+
+// Print one image's reports as a table.
+//
+fn print_report(path: &str, report: &ArtworkReport) {
+    println!("{}", path);
+    for result in &report.reports {
+        let metric = match result.metric {
+            Some(n) => format!("{}", n),
+            None => "???".to_string(),
+        };
+        let postfix = if result.display_percent { "%" } else { "" };
+        println!(
+            "  {:<22}{:>6}{:<2}{:?}",
+            result.label, metric, postfix, result.status
+        );
+    }
+    if let Some(warning) = &report.embedded_dpi_warning {
+        println!("  Warning: {}", warning);
+    }
+}
+
+fn main() {
+    let mut json = false;
+    let mut side = ShirtSide::Front;
+    let mut embedded_size = false;
+    let mut shirt_color = ShirtColorCatalog::default().colors[0].color();
+    let mut settings = ReportSettings::default();
+    let mut paths: Vec<String> = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--back" => side = ShirtSide::Back,
+            "--embedded-size" => embedded_size = true,
+            "--shirt-color" => {
+                let hex = args.next().unwrap_or_else(|| usage());
+                shirt_color = shirt_color_from_hex(&hex).unwrap_or_else(|| usage());
+            }
+            "--settings" => {
+                let settings_path = args.next().unwrap_or_else(|| usage());
+                settings = load_settings(&settings_path).unwrap_or_else(|msg| {
+                    eprintln!("{}", msg);
+                    std::process::exit(EXIT_ERROR);
+                });
+            }
+            flag if flag.starts_with("--") => {
+                eprintln!("unknown option: {}", flag);
+                usage();
+            }
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        usage();
+    }
+
+    let report_templates = ReportTemplates::new();
+
+    let mut file_reports = Vec::new();
+    for path in paths {
+        match check_artwork(
+            &path,
+            &report_templates,
+            &settings,
+            side,
+            embedded_size,
+            shirt_color,
+        ) {
+            Ok(report) => file_reports.push(FileReport { file: path, report }),
+            Err(msg) => {
+                eprintln!("{}", msg);
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&file_reports).unwrap());
+    } else {
+        for file_report in &file_reports {
+            print_report(&file_report.file, &file_report.report);
+        }
+    }
+
+    let any_failed = file_reports.iter().any(|file_report| {
+        file_report
+            .report
+            .reports
+            .iter()
+            .any(|result| result.status == ReportStatus::Fail)
+    });
+    if any_failed {
+        std::process::exit(EXIT_FAIL);
+    }
+}
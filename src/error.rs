@@ -8,6 +8,8 @@
 pub enum ErrorTypes {
     FileImportAborted,
     ImageLoadError,
+    FileExportError,
+    CatalogLoadError,
 }
 
 // Error class (error id and a user facing message describing what went wrong)
@@ -0,0 +1,202 @@
+//! Halo and fringe detection
+//!
+//! Design tools often export anti-aliased edges blended against a matte color
+//! (usually white) as well as made partially transparent.  Once the partial
+//! transparency fix makes the edge solid, the matte's share of the color is left
+//! behind as a light halo around the art - very visible on a dark shirt.
+//!
+//! Each partially transparent edge pixel is compared with the opaque art next to it.
+//! If the edge pixel is what the art would look like blended with a matte color, it's
+//! a fringe.  The defringe fix takes the matte back out before the alpha is fixed.
+
+use crate::image_utils::*;
+use crate::loaded_image::*;
+
+// The matte colors that are looked for
+const MATTES: [egui::Color32; 2] = [egui::Color32::WHITE, egui::Color32::BLACK];
+// Edge pixels closer than this to the art next to them don't show a fringe
+const MIN_FRINGE_DISTANCE: u32 = 24;
+// The Fringe report's tool paints fringe pixels this color
+const FRINGE_FLAG: egui::Color32 = egui::Color32::from_rgb(255, 0, 255);
+
+fn blend(art: egui::Color32, matte: egui::Color32, alpha: f32) -> egui::Color32 {
+    let mix = |a: u8, m: u8| (a as f32 * alpha + m as f32 * (1.0 - alpha)).round() as u8;
+    egui::Color32::from_rgb(
+        mix(art.r(), matte.r()),
+        mix(art.g(), matte.g()),
+        mix(art.b(), matte.b()),
+    )
+}
+
+// The average color of the opaque pixels around a partially transparent one, if
+// there are any.
+//
+fn opaque_neighbours(art: &LoadedImage, index: usize) -> Option<egui::Color32> {
+    let [width, height] = *art.size_as_array();
+    let (x, y) = (index % width, index / width);
+    let mut sum = [0u32; 3];
+    let mut count = 0;
+    for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+        for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+            let pixel = art.pixels()[nx + ny * width];
+            if pixel.a() == 255 {
+                sum[0] += pixel.r() as u32;
+                sum[1] += pixel.g() as u32;
+                sum[2] += pixel.b() as u32;
+                count += 1;
+            }
+        }
+    }
+    (count > 0).then(|| {
+        egui::Color32::from_rgb(
+            (sum[0] / count) as u8,
+            (sum[1] / count) as u8,
+            (sum[2] / count) as u8,
+        )
+    })
+}
+
+// Is the edge pixel the art next to it blended with the matte?
+//
+fn is_fringe(pixel: egui::Color32, inside: egui::Color32, matte: egui::Color32) -> bool {
+    let color = make_opaque(&pixel);
+    let blended = blend(inside, matte, pixel.a() as f32 / 255.0);
+    let from_inside = color_distance(color, inside);
+    from_inside > MIN_FRINGE_DISTANCE && color_distance(color, blended) < from_inside
+}
+
+/// How much of the art's anti-aliased edge was blended with a matte color
+pub struct FringeCheck {
+    matte: Option<egui::Color32>,
+    fringe_percent: u32,
+    flagged: LoadedImage,
+}
+
+impl FringeCheck {
+    pub fn new(art: &LoadedImage) -> Self {
+        // Partially transparent pixels next to opaque ones, and the color beside them
+        let edges: Vec<(usize, egui::Color32)> = art
+            .pixels()
+            .iter()
+            .enumerate()
+            .filter(|(_, pixel)| pixel.a() != 0 && pixel.a() != 255)
+            .filter_map(|(index, _)| Some((index, opaque_neighbours(art, index)?)))
+            .collect();
+
+        // Whichever matte the most edge pixels were blended with
+        let (matte, fringes) = MATTES
+            .iter()
+            .map(|matte| {
+                let fringes: Vec<usize> = edges
+                    .iter()
+                    .filter(|(index, inside)| is_fringe(art.pixels()[*index], *inside, *matte))
+                    .map(|(index, _)| *index)
+                    .collect();
+                (*matte, fringes)
+            })
+            .max_by_key(|(_, fringes)| fringes.len())
+            .unwrap();
+
+        let mut pixels = art.pixels().clone();
+        fringes
+            .iter()
+            .for_each(|index| pixels[*index] = FRINGE_FLAG);
+        Self {
+            matte: (!fringes.is_empty()).then_some(matte),
+            fringe_percent: (100 * fringes.len()).div_ceil(edges.len().max(1)) as u32,
+            flagged: load_image_from_existing_size(art, pixels, "fringe_flagged"),
+        }
+    }
+
+    /// The matte color the edges were blended with, if they were
+    pub fn matte(&self) -> Option<egui::Color32> {
+        self.matte
+    }
+
+    /// Percentage of the anti-aliased edge pixels with a fringe
+    pub fn fringe_percent(&self) -> u32 {
+        self.fringe_percent
+    }
+
+    /// The art with the fringe painted a bright color
+    pub fn flagged(&self) -> &LoadedImage {
+        &self.flagged
+    }
+}
+
+/// The art with the matte color taken back out of its partially transparent pixels.
+/// Each pixel is treated as the art blended with the matte, and the blend is undone.
+pub fn defringe(art: &LoadedImage, matte: egui::Color32, name: impl Into<String>) -> LoadedImage {
+    load_image_from_existing_image(
+        art,
+        &|pixel: &egui::Color32| {
+            if pixel.a() == 0 || pixel.a() == 255 {
+                return *pixel;
+            }
+            let alpha = pixel.a() as f32 / 255.0;
+            let color = make_opaque(pixel);
+            let unblend = |c: u8, m: u8| {
+                ((c as f32 - m as f32 * (1.0 - alpha)) / alpha)
+                    .round()
+                    .clamp(0.0, 255.0) as u8
+            };
+            egui::Color32::from_rgba_unmultiplied(
+                unblend(color.r(), matte.r()),
+                unblend(color.g(), matte.g()),
+                unblend(color.b(), matte.b()),
+                pixel.a(),
+            )
+        },
+        name,
+    )
+}
+
+#[cfg(test)]
+mod fringe_should {
+    use super::*;
+
+    // A 6x6 red square with a half transparent edge pixel around it, the edge colored
+    // by the given function.
+    fn square_with_edge(edge: egui::Color32) -> LoadedImage {
+        let square = |x, y| match (x, y) {
+            (2..=7, 2..=7) => egui::Color32::RED,
+            (1..=8, 1..=8) => edge,
+            _ => egui::Color32::TRANSPARENT,
+        };
+        load_image_from_function([10, 10], square, "square")
+    }
+
+    fn white_fringe() -> LoadedImage {
+        square_with_edge(egui::Color32::from_rgba_unmultiplied(255, 128, 128, 128))
+    }
+
+    #[test]
+    fn find_edges_blended_with_a_matte() {
+        let check = FringeCheck::new(&white_fringe());
+        assert_eq!(Some(egui::Color32::WHITE), check.matte());
+        assert_eq!(100, check.fringe_percent());
+        assert_eq!(FRINGE_FLAG, check.flagged().pixels()[11]);
+        assert_eq!(egui::Color32::RED, check.flagged().pixels()[22]);
+
+        let black = square_with_edge(egui::Color32::from_rgba_unmultiplied(128, 0, 0, 128));
+        assert_eq!(Some(egui::Color32::BLACK), FringeCheck::new(&black).matte());
+    }
+
+    #[test]
+    fn leave_clean_edges_alone() {
+        let clean = square_with_edge(egui::Color32::from_rgba_unmultiplied(255, 0, 0, 128));
+        let check = FringeCheck::new(&clean);
+        assert_eq!(None, check.matte());
+        assert_eq!(0, check.fringe_percent());
+    }
+
+    #[test]
+    fn take_the_matte_back_out() {
+        let fixed = defringe(&white_fringe(), egui::Color32::WHITE, "defringed");
+        let edge = make_opaque(&fixed.pixels()[11]);
+        assert!(edge.r() > 250 && edge.g() < 8 && edge.b() < 8, "{:?}", edge);
+        assert_eq!(128, fixed.pixels()[11].a());
+        assert_eq!(egui::Color32::RED, fixed.pixels()[22]);
+        assert_eq!(0, FringeCheck::new(&fixed).fringe_percent());
+    }
+}
@@ -0,0 +1,259 @@
+//! Garment mockups
+//!
+//! The built in t-shirt photo isn't the only thing art gets printed on.  Users can
+//! import their own mockup photos (hoodies, tank tops, tote bags), then calibrate
+//! where each print area sits on the photo by dragging its corners.  The
+//! calibration is saved with the garment.
+//!
+//! Garments are saved in the app's storage, which is browser local storage in the
+//! web build and only holds a few megabytes, so big photos are shrunk on import.
+
+use crate::error::*;
+use crate::loaded_image::*;
+use crate::math::*;
+use crate::print_area::*;
+use base64::Engine;
+use std::sync::Arc;
+
+// Garment photos are shrunk to fit in this many pixels on a side.  The built in t-shirt
+// photos are smaller than this, so it's plenty for a mockup.
+const MAX_GARMENT_SIZE: u32 = 512;
+// JPEG quality for shrunk photos
+const GARMENT_JPEG_QUALITY: u8 = 85;
+
+// Calibrated print areas narrower or shorter than this, in t-shirt space, are refused.
+// A corner dragged on to the row or column of the opposite corner would otherwise
+// make a print area with no area, that art can't be mapped in or out of.
+const MIN_CALIBRATED_SIZE: f32 = 0.01;
+
+/// Where the print areas sit on a garment photo, in t-shirt space.  Print areas
+/// that haven't been calibrated use the same spot they'd have on the t-shirt.
+#[derive(Debug, Default, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GarmentCalibration {
+    areas: Vec<(PrintArea, egui::Rect)>,
+}
+
+impl GarmentCalibration {
+    /// The calibrated rectangle for a print area, if there is one
+    pub fn get(&self, print_area: PrintArea) -> Option<egui::Rect> {
+        self.areas
+            .iter()
+            .find(|(area, rect)| *area == print_area && is_big_enough(rect))
+            .map(|(_, rect)| *rect)
+    }
+
+    /// Calibrate a print area.  Rectangles too small to print on are ignored.
+    pub fn set(&mut self, print_area: PrintArea, rect: egui::Rect) {
+        if !is_big_enough(&rect) {
+            return;
+        }
+        self.reset(print_area);
+        self.areas.push((print_area, rect));
+    }
+
+    /// Forget the calibration for a print area, so it's back in its default spot
+    pub fn reset(&mut self, print_area: PrintArea) {
+        self.areas.retain(|(area, _)| *area != print_area);
+    }
+}
+
+fn is_big_enough(rect: &egui::Rect) -> bool {
+    rect.width() >= MIN_CALIBRATED_SIZE && rect.height() >= MIN_CALIBRATED_SIZE
+}
+
+/// An imported garment photo and its print area calibration
+#[derive(Clone)]
+pub struct Garment {
+    name: String,
+    image: LoadedImage,
+    // The photo's image file, base64 encoded.  This is what gets saved between runs,
+    // so it's encoded once instead of on every save.
+    saved_image: Arc<String>,
+    calibration: GarmentCalibration,
+    // The fabric color, sampled from where a full front print would go
+    color: egui::Color32,
+}
+
+/// A garment in a form that can be saved between runs
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SavedGarment {
+    name: String,
+    // The imported photo, base64 encoded
+    image: String,
+    calibration: GarmentCalibration,
+}
+
+impl Garment {
+    /// Create a garment from the bytes of an image file.  Photos bigger than
+    /// MAX_GARMENT_SIZE are shrunk.
+    pub fn from_image_file(name: impl Into<String>, image_file: Vec<u8>) -> Result<Self, Error> {
+        let name = name.into();
+        let image_file = shrink_image_file(image_file)?;
+        let image = load_image_from_untrusted_source(&image_file, name.clone())?;
+        let color = average_color(
+            &image,
+            print_area_on_tshirt(image.size(), PrintArea::FullFront),
+        );
+        Ok(Self {
+            name,
+            image,
+            color,
+            saved_image: Arc::new(base64::engine::general_purpose::STANDARD.encode(image_file)),
+            calibration: GarmentCalibration::default(),
+        })
+    }
+
+    pub fn from_saved(saved: SavedGarment) -> Result<Self, Error> {
+        let image_file = base64::engine::general_purpose::STANDARD
+            .decode(saved.image)
+            .map_err(|e| Error::new(ErrorTypes::ImageLoadError, e.to_string()))?;
+        let mut garment = Self::from_image_file(saved.name, image_file)?;
+        garment.calibration = saved.calibration;
+        Ok(garment)
+    }
+
+    pub fn to_saved(&self) -> SavedGarment {
+        SavedGarment {
+            name: self.name.clone(),
+            image: self.saved_image.to_string(),
+            calibration: self.calibration.clone(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn image(&self) -> &LoadedImage {
+        &self.image
+    }
+
+    /// The color of the garment's fabric
+    pub fn color(&self) -> egui::Color32 {
+        self.color
+    }
+
+    pub fn calibration_mut(&mut self) -> &mut GarmentCalibration {
+        &mut self.calibration
+    }
+
+    /// Where the print area is on the garment photo, in t-shirt space
+    pub fn print_area_rect(&self, print_area: PrintArea) -> egui::Rect {
+        self.calibration
+            .get(print_area)
+            .unwrap_or_else(|| print_area_on_tshirt(self.image.size(), print_area))
+    }
+}
+
+// Shrink an image file that's bigger than MAX_GARMENT_SIZE on a side.  Shrunk photos
+// are saved as JPEGs, unless they have transparency.
+//
+fn shrink_image_file(image_file: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let to_error = |e: image::ImageError| Error::new(ErrorTypes::ImageLoadError, e.to_string());
+    let image = image::load_from_memory(&image_file).map_err(to_error)?;
+    if image.width() <= MAX_GARMENT_SIZE && image.height() <= MAX_GARMENT_SIZE {
+        return Ok(image_file);
+    }
+    let image = image.thumbnail(MAX_GARMENT_SIZE, MAX_GARMENT_SIZE);
+    let has_transparency =
+        image.color().has_alpha() && image.to_rgba8().pixels().any(|p| p[3] < 255);
+    let mut shrunk = std::io::Cursor::new(Vec::new());
+    if has_transparency {
+        image.write_to(&mut shrunk, image::ImageFormat::Png)
+    } else {
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut shrunk, GARMENT_JPEG_QUALITY)
+            .encode_image(&image.to_rgb8())
+    }
+    .map_err(to_error)?;
+    Ok(shrunk.into_inner())
+}
+
+// The average color of the opaque pixels inside a rectangle in t-shirt space
+//
+fn average_color(image: &LoadedImage, rect: egui::Rect) -> egui::Color32 {
+    let [width, height] = *image.size_as_array();
+    let x_range = (rect.min.x * width as f32) as usize..(rect.max.x * width as f32) as usize;
+    let y_range = (rect.min.y * height as f32) as usize..(rect.max.y * height as f32) as usize;
+    let mut sum = [0u64; 3];
+    let mut count = 0u64;
+    for y in y_range.start.min(height)..y_range.end.min(height) {
+        for x in x_range.start.min(width)..x_range.end.min(width) {
+            let pixel = image.pixels()[y * width + x];
+            if pixel.a() == 255 {
+                sum[0] += pixel.r() as u64;
+                sum[1] += pixel.g() as u64;
+                sum[2] += pixel.b() as u64;
+                count += 1;
+            }
+        }
+    }
+    let [r, g, b] = sum.map(|channel| (channel / count.max(1)) as u8);
+    egui::Color32::from_rgb(r, g, b)
+}
+
+#[cfg(test)]
+mod garment_should {
+    use super::*;
+
+    #[test]
+    fn default_to_the_tshirt_print_area() {
+        let bytes = include_bytes!("../assets/blue_tshirt.png").to_vec();
+        let mut garment = Garment::from_image_file("Tote Bag", bytes).unwrap();
+        let default = print_area_on_tshirt(garment.image().size(), PrintArea::FullFront);
+        assert_eq!(default, garment.print_area_rect(PrintArea::FullFront));
+
+        let rect = egui::Rect::from_min_max(egui::pos2(0.1, 0.2), egui::pos2(0.6, 0.9));
+        garment.calibration_mut().set(PrintArea::FullFront, rect);
+        assert_eq!(rect, garment.print_area_rect(PrintArea::FullFront));
+        assert_ne!(rect, garment.print_area_rect(PrintArea::LeftChest));
+
+        garment.calibration_mut().reset(PrintArea::FullFront);
+        assert_eq!(default, garment.print_area_rect(PrintArea::FullFront));
+    }
+
+    #[test]
+    fn refuse_print_areas_with_no_area() {
+        let mut calibration = GarmentCalibration::default();
+        let rect = egui::Rect::from_min_max(egui::pos2(0.1, 0.2), egui::pos2(0.6, 0.9));
+        calibration.set(PrintArea::FullFront, rect);
+        let flat = egui::Rect::from_two_pos(egui::pos2(0.1, 0.2), egui::pos2(0.6, 0.2));
+        calibration.set(PrintArea::FullFront, flat);
+        assert_eq!(Some(rect), calibration.get(PrintArea::FullFront));
+    }
+
+    #[test]
+    fn sample_the_fabric_color() {
+        let bytes = include_bytes!("../assets/blue_tshirt.png").to_vec();
+        let garment = Garment::from_image_file("Blue", bytes).unwrap();
+        let color = garment.color();
+        assert!(color.b() > color.g() && color.g() > color.r());
+    }
+
+    #[test]
+    fn survive_being_saved() {
+        let bytes = include_bytes!("../assets/blue_tshirt.png").to_vec();
+        let mut garment = Garment::from_image_file("Hoodie", bytes).unwrap();
+        let rect = egui::Rect::from_min_max(egui::pos2(0.3, 0.3), egui::pos2(0.7, 0.6));
+        garment.calibration_mut().set(PrintArea::Pocket, rect);
+
+        let json = serde_json::to_string(&garment.to_saved()).unwrap();
+        let restored = Garment::from_saved(serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!("Hoodie", restored.name());
+        assert_eq!(garment.image().size(), restored.image().size());
+        assert_eq!(rect, restored.print_area_rect(PrintArea::Pocket));
+    }
+
+    #[test]
+    fn shrink_big_photos() {
+        let photo = image::RgbImage::from_pixel(2000, 1000, image::Rgb([30, 60, 200]));
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        photo.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        let garment = Garment::from_image_file("Banner", bytes.into_inner()).unwrap();
+        assert_eq!([512, 256], *garment.image().size_as_array());
+
+        let saved = garment.to_saved();
+        assert!(saved.image.len() < 64 * 1024, "{}", saved.image.len());
+        let restored = Garment::from_saved(saved).unwrap();
+        assert_eq!([512, 256], *restored.image().size_as_array());
+    }
+}
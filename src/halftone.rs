@@ -0,0 +1,288 @@
+//! Halftone fix for partial transparency
+//!
+//! T-Shirt printing can't do partial transparency, so the simple fix drops every
+//! pixel that isn't fully opaque - soft shadows and fades vanish.  Printers handle
+//! fades with halftones instead: a grid of solid dots, bigger where the art is more
+//! opaque.  From a distance the dots blend into the fade.
+//!
+//! The dot grid is measured in lines per inch (LPI), so the size of a dot in pixels
+//! depends on the resolution the art is printed at.
+
+use crate::image_utils::*;
+use crate::loaded_image::*;
+use std::sync::Mutex;
+
+// Dot cells are never smaller than this many pixels, whatever the DPI and LPI
+const MIN_CELL_PIXELS: f32 = 2.0;
+// Dot cells this many pixels across have enough room for a smooth range of dot sizes
+const SUGGESTED_CELL_PIXELS: f32 = 6.0;
+// Screen printers' usual range of halftone LPIs
+const MIN_LPI: u32 = 25;
+const MAX_LPI: u32 = 65;
+// Size of the before/ after preview, in art pixels
+const PREVIEW_SIZE: usize = 160;
+
+/// The shape of the halftone dots
+#[derive(Debug, PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum DotShape {
+    Round,
+    Square,
+    Line,
+}
+
+impl DotShape {
+    pub const ALL: [DotShape; 3] = [DotShape::Round, DotShape::Square, DotShape::Line];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DotShape::Round => "Round",
+            DotShape::Square => "Square",
+            DotShape::Line => "Line",
+        }
+    }
+
+    // How much of the cell has to be covered before the point (u, v) is in the dot.
+    // u and v are from the center of the cell, in cells (so -0.5 to 0.5).  Covering
+    // the cell to the returned fraction covers the point.
+    //
+    fn threshold(&self, u: f32, v: f32) -> f32 {
+        match self {
+            DotShape::Round => {
+                // Circles grow until they touch at a radius of 0.5, then the corners
+                // fill in.
+                const TOUCHING: f32 = std::f32::consts::PI / 4.0;
+                let radius = (u * u + v * v).sqrt();
+                if radius <= 0.5 {
+                    std::f32::consts::PI * radius * radius
+                } else {
+                    let to_corner = (radius - 0.5) / (std::f32::consts::FRAC_1_SQRT_2 - 0.5);
+                    TOUCHING + (1.0 - TOUCHING) * to_corner
+                }
+            }
+            DotShape::Square => {
+                let half_width = u.abs().max(v.abs());
+                4.0 * half_width * half_width
+            }
+            DotShape::Line => 2.0 * v.abs(),
+        }
+    }
+}
+
+/// How to halftone the art
+#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HalftoneSettings {
+    pub shape: DotShape,
+    /// Lines (rows of dots) per inch
+    pub lpi: u32,
+    /// Angle of the rows of dots, in degrees
+    pub angle: f32,
+}
+
+impl Default for HalftoneSettings {
+    fn default() -> Self {
+        Self {
+            shape: DotShape::Round,
+            lpi: 45,
+            angle: 22.5,
+        }
+    }
+}
+
+impl HalftoneSettings {
+    /// A line screen that suits art printed at dpi
+    pub fn suggested_lpi(dpi: f32) -> u32 {
+        ((dpi / SUGGESTED_CELL_PIXELS).round() as u32).clamp(MIN_LPI, MAX_LPI)
+    }
+
+    /// The size of one dot cell, in pixels, for art printed at dpi
+    pub fn cell_pixels(&self, dpi: f32) -> f32 {
+        (dpi / self.lpi.max(1) as f32).max(MIN_CELL_PIXELS)
+    }
+}
+
+// Halftone the pixels of an image, where origin is the top left corner of the pixels
+// in the full image.  Using the full image's coordinates keeps the dots lined
+// up between a preview and the real thing.
+//
+fn halftone_pixels(
+    pixels: &[egui::Color32],
+    size: [usize; 2],
+    origin: [usize; 2],
+    dpi: f32,
+    settings: &HalftoneSettings,
+) -> Vec<egui::Color32> {
+    let cell = settings.cell_pixels(dpi);
+    let (sin, cos) = settings.angle.to_radians().sin_cos();
+    pixels
+        .iter()
+        .enumerate()
+        .map(|(i, pixel)| {
+            if pixel.a() == 0 || pixel.a() == 255 {
+                return *pixel;
+            }
+            let x = (origin[0] + i % size[0]) as f32 + 0.5;
+            let y = (origin[1] + i / size[0]) as f32 + 0.5;
+            let u = (x * cos + y * sin) / cell;
+            let v = (y * cos - x * sin) / cell;
+            let threshold = settings.shape.threshold(u - u.round(), v - v.round());
+            let coverage = pixel.a() as f32 / 255.0;
+            if threshold < coverage {
+                make_opaque(pixel)
+            } else {
+                egui::Color32::TRANSPARENT
+            }
+        })
+        .collect()
+}
+
+/// Replace partial transparency with a pattern of opaque dots.  Fully opaque and
+/// fully transparent pixels are left alone.
+pub fn halftone(
+    art: &LoadedImage,
+    dpi: f32,
+    settings: &HalftoneSettings,
+    name: impl Into<String>,
+) -> LoadedImage {
+    let size = *art.size_as_array();
+    let pixels = halftone_pixels(art.pixels(), size, [0, 0], dpi, settings);
+    load_image_from_existing_size(art, pixels, name)
+}
+
+/// A close up of the art's partial transparency, and what it looks like halftoned
+pub struct HalftonePreview {
+    before: LoadedImage,
+    origin: [usize; 2],
+    // The last halftone settings and DPI previewed
+    after: Mutex<Option<(HalftoneSettings, f32, LoadedImage)>>,
+}
+
+impl HalftonePreview {
+    /// Preview the part of the art with the most partial transparency.
+    pub fn new(art: &LoadedImage) -> Self {
+        let [width, height] = *art.size_as_array();
+        let crop = [PREVIEW_SIZE.min(width), PREVIEW_SIZE.min(height)];
+
+        // Count partially transparent pixels in crop sized blocks.
+        let blocks_x = width.div_ceil(crop[0]);
+        let blocks_y = height.div_ceil(crop[1]);
+        let mut counts = vec![0usize; blocks_x * blocks_y];
+        for (i, pixel) in art.pixels().iter().enumerate() {
+            if pixel.a() != 0 && pixel.a() != 255 {
+                counts[(i / width) / crop[1] * blocks_x + (i % width) / crop[0]] += 1;
+            }
+        }
+        let busiest = (0..counts.len()).max_by_key(|&b| counts[b]).unwrap_or(0);
+        let origin = [
+            ((busiest % blocks_x) * crop[0]).min(width - crop[0]),
+            ((busiest / blocks_x) * crop[1]).min(height - crop[1]),
+        ];
+
+        let pixels = (0..crop[1])
+            .flat_map(|y| {
+                let start = (origin[1] + y) * width + origin[0];
+                art.pixels()[start..start + crop[0]].iter().copied()
+            })
+            .collect();
+        Self {
+            before: load_image_from_pixels(pixels, crop, "halftone_before"),
+            origin,
+            after: Mutex::new(None),
+        }
+    }
+
+    pub fn before(&self) -> &LoadedImage {
+        &self.before
+    }
+
+    pub fn after(&self, dpi: f32, settings: &HalftoneSettings) -> LoadedImage {
+        let mut after = self.after.lock().unwrap();
+        match after.as_ref() {
+            Some((s, d, image)) if s == settings && *d == dpi => image.clone(),
+            _ => {
+                let size = *self.before.size_as_array();
+                let pixels =
+                    halftone_pixels(self.before.pixels(), size, self.origin, dpi, settings);
+                let image = load_image_from_pixels(pixels, size, "halftone_after");
+                *after = Some((*settings, dpi, image.clone()));
+                image
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod halftone_should {
+    use super::*;
+
+    // A fade from transparent on the left to opaque red on the right, above a solid
+    // band of opaque blue.
+    fn fade_art() -> LoadedImage {
+        let fade = |x, y| {
+            if y >= 100 {
+                egui::Color32::BLUE
+            } else {
+                let alpha = (x * 255 / 199) as u8;
+                egui::Color32::from_rgba_unmultiplied(255, 0, 0, alpha)
+            }
+        };
+        load_image_from_function([200, 120], fade, "fade")
+    }
+
+    fn coverage(image: &LoadedImage, columns: std::ops::Range<usize>) -> f32 {
+        let width = image.size_as_array()[0];
+        let in_columns: Vec<_> = image.pixels()[..100 * width]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| columns.contains(&(i % width)))
+            .collect();
+        let covered = in_columns.iter().filter(|(_, p)| p.a() == 255).count();
+        covered as f32 / in_columns.len() as f32
+    }
+
+    #[test]
+    fn turn_partial_transparency_into_dots() {
+        let art = fade_art();
+        for shape in DotShape::ALL {
+            let settings = HalftoneSettings {
+                shape,
+                ..Default::default()
+            };
+            let fixed = halftone(&art, 300.0, &settings, "fixed");
+            assert!(fixed.pixels().iter().all(|p| p.a() == 0 || p.a() == 255));
+            // Solid ink is left alone
+            assert_eq!(art.pixels()[110 * 200..], fixed.pixels()[110 * 200..]);
+            // The fade becomes a fade of dots.
+            let light = coverage(&fixed, 10..40);
+            let middle = coverage(&fixed, 85..115);
+            let heavy = coverage(&fixed, 160..190);
+            assert!(light < 0.25, "{:?} light {}", shape, light);
+            assert!((middle - 0.5).abs() < 0.15, "{:?} middle {}", shape, middle);
+            assert!(heavy > 0.75, "{:?} heavy {}", shape, heavy);
+            // Dots are the art's color, not darkened by the alpha.
+            let dot = fixed.pixels().iter().find(|p| p.a() == 255).unwrap();
+            assert_eq!(egui::Color32::RED, *dot);
+        }
+    }
+
+    #[test]
+    fn size_dots_from_the_dpi_and_lpi() {
+        let settings = HalftoneSettings::default();
+        assert_eq!(300.0 / 45.0, settings.cell_pixels(300.0));
+        assert_eq!(MIN_CELL_PIXELS, settings.cell_pixels(50.0));
+        assert_eq!(50, HalftoneSettings::suggested_lpi(300.0));
+        assert_eq!(MIN_LPI, HalftoneSettings::suggested_lpi(72.0));
+        assert_eq!(MAX_LPI, HalftoneSettings::suggested_lpi(1200.0));
+    }
+
+    #[test]
+    fn preview_the_partial_transparency() {
+        let art = fade_art();
+        let preview = HalftonePreview::new(&art);
+        assert_eq!([160, 120], *preview.before().size_as_array());
+        let settings = HalftoneSettings::default();
+        let after = preview.after(300.0, &settings);
+        let fixed = halftone(&art, 300.0, &settings, "fixed");
+        // The preview's dots line up with the real thing
+        assert_eq!(fixed.pixels()[50 * 200 + 40], after.pixels()[50 * 160]);
+    }
+}
@@ -313,6 +313,25 @@ impl Hsla {
         }
     }
 
+    /// Calculate a fixed point table that maps source to target.
+    ///
+    /// Uses a gamma table when it can.  A gamma curve can't move values away from 0 or
+    /// 1, so if the source or target is 0 or 1 a linear scale is used instead, i.e.,
+    /// mapping a fully saturated blue to a grey with no saturation.  A source of 0
+    /// can't be scaled, so that case stays the identity function.
+    ///
+    fn calc_mapping_table(source: f32, target: f32) -> Vec<u16> {
+        let gamma_works = source != 1.0 && target != 0.0 && target != 1.0;
+        if source == 0.0 || gamma_works {
+            Self::calc_gamma_table(source, target)
+        } else {
+            let scale = target / source;
+            (0..=ONE_U16)
+                .map(|n| ((n as f32) * scale).min(HSLA_ONE_F) as u16)
+                .collect()
+        }
+    }
+
     /// Generate a data structure that transforms HSLA space from source to target
     ///
     ///
@@ -327,11 +346,11 @@ impl Hsla {
 
         let source_s = (source_hsla.s as f32) / HSLA_ONE_F;
         let target_s = (target_hsla.s as f32) / HSLA_ONE_F;
-        let st = Hsla::calc_gamma_table(source_s, target_s);
+        let st = Hsla::calc_mapping_table(source_s, target_s);
 
         let source_l = (source_hsla.l as f32) / HSLA_ONE_F;
         let target_l = (target_hsla.l as f32) / HSLA_ONE_F;
-        let lt = Hsla::calc_gamma_table(source_l, target_l);
+        let lt = Hsla::calc_mapping_table(source_l, target_l);
 
         Box::new(move |input: &egui::Color32| -> egui::Color32 {
             let input_hsla = Hsla::from(input);
@@ -455,6 +474,20 @@ mod tests {
         assert_eq!(table_s0, table_t1);
     }
 
+    #[test]
+    fn transform_saturated_colors_to_greys() {
+        let blue = egui::Color32::from_rgb(0, 143, 190);
+        let grey = egui::Color32::from_rgb(154, 154, 154);
+        let blue_to_grey = Hsla::calc_hsla_transform(blue, grey);
+        let result = blue_to_grey(&blue);
+        assert!(
+            is_close(&grey, &result),
+            "expected = {:?} actual = {:?}",
+            grey,
+            result
+        );
+    }
+
     #[test]
     fn do_hsla_transforms_properly() {
         let green = egui::Color32::from_rgb(0, 255, 0);
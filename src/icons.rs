@@ -13,6 +13,7 @@ pub enum Icon {
     Tool,
     Import,
     FixPT,
+    Export,
 }
 
 pub struct IconStorage {
@@ -24,97 +25,59 @@ pub struct IconStorage {
     tool: LoadedImage,
     import: LoadedImage,
     partial_transparency_fix: LoadedImage,
+    export: LoadedImage,
     icon_last_cycle: SystemTime,
     cycle: usize,
+    // Icons are only ever displayed, so keep the context around to create their textures.
+    ctx: egui::Context,
 }
 
 impl IconStorage {
     pub fn new(ctx: &egui::Context) -> Self {
-        let logo: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/tsquared.png"),
-            "tsquared",
-            ctx,
-        );
+        let logo: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/tsquared.png"), "tsquared");
         let pass: LoadedImage =
-            load_image_from_trusted_source(include_bytes!("../assets/pass.png"), "pass", ctx);
+            load_image_from_trusted_source(include_bytes!("../assets/pass.png"), "pass");
         let warn: LoadedImage =
-            load_image_from_trusted_source(include_bytes!("../assets/warn.png"), "warn", ctx);
+            load_image_from_trusted_source(include_bytes!("../assets/warn.png"), "warn");
         let fail: LoadedImage =
-            load_image_from_trusted_source(include_bytes!("../assets/fail.png"), "fail", ctx);
+            load_image_from_trusted_source(include_bytes!("../assets/fail.png"), "fail");
         let tool: LoadedImage =
-            load_image_from_trusted_source(include_bytes!("../assets/tool.png"), "tool", ctx);
+            load_image_from_trusted_source(include_bytes!("../assets/tool.png"), "tool");
 
         // TODO, right way to do this in Rust.
-        let loading_01: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_01.png"),
-            "loading",
-            ctx,
-        );
-        let loading_02: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_02.png"),
-            "loading",
-            ctx,
-        );
-        let loading_03: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_03.png"),
-            "loading",
-            ctx,
-        );
-        let loading_04: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_04.png"),
-            "loading",
-            ctx,
-        );
-        let loading_05: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_05.png"),
-            "loading",
-            ctx,
-        );
-        let loading_06: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_06.png"),
-            "loading",
-            ctx,
-        );
-        let loading_07: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_07.png"),
-            "loading",
-            ctx,
-        );
-        let loading_08: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_08.png"),
-            "loading",
-            ctx,
-        );
-        let loading_09: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_09.png"),
-            "loading",
-            ctx,
-        );
-        let loading_10: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_10.png"),
-            "loading",
-            ctx,
-        );
-        let loading_11: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_11.png"),
-            "loading",
-            ctx,
-        );
-        let loading_12: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/spinner_12.png"),
-            "loading",
-            ctx,
-        );
-        let import: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/import_80x80.png"),
-            "import",
-            ctx,
-        );
+        let loading_01: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_01.png"), "loading");
+        let loading_02: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_02.png"), "loading");
+        let loading_03: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_03.png"), "loading");
+        let loading_04: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_04.png"), "loading");
+        let loading_05: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_05.png"), "loading");
+        let loading_06: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_06.png"), "loading");
+        let loading_07: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_07.png"), "loading");
+        let loading_08: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_08.png"), "loading");
+        let loading_09: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_09.png"), "loading");
+        let loading_10: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_10.png"), "loading");
+        let loading_11: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_11.png"), "loading");
+        let loading_12: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/spinner_12.png"), "loading");
+        let import: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/import_80x80.png"), "import");
         let partial_transparency_fix: LoadedImage = load_image_from_trusted_source(
             include_bytes!("../assets/partialt_80x80.png"),
             "partialt",
-            ctx,
         );
+        let export: LoadedImage =
+            load_image_from_trusted_source(include_bytes!("../assets/export_80x80.png"), "export");
 
         Self {
             logo,
@@ -128,8 +91,10 @@ impl IconStorage {
             tool,
             import,
             partial_transparency_fix,
+            export,
             icon_last_cycle: SystemTime::now(),
             cycle: 0,
+            ctx: ctx.clone(),
         }
     }
 
@@ -142,14 +107,15 @@ impl IconStorage {
             Icon::Tool => &self.tool,
             Icon::Import => &self.import,
             Icon::FixPT => &self.partial_transparency_fix,
+            Icon::Export => &self.export,
         }
     }
     pub fn texture_handle(&self, icon: Icon) -> &egui::TextureHandle {
-        self.get_loaded_image(icon).texture_handle()
+        self.get_loaded_image(icon).texture_handle(&self.ctx)
     }
 
     pub fn load_animation(&self) -> &egui::TextureHandle {
-        self.loading[self.cycle % 12].texture_handle()
+        self.loading[self.cycle % 12].texture_handle(&self.ctx)
     }
 
     pub fn status_icon(&self, status: ReportStatus) -> egui::Image<'_> {
@@ -0,0 +1,222 @@
+//! Embedded image resolution
+//!
+//! Art packages record the resolution the art was made for - a pHYs chunk in a
+//! .png file, or the JFIF header/ EXIF resolution tags in a .jpg file.  The
+//! resolution tells us the size the art was meant to be printed at.  The image
+//! decoders throw this away, so the file is read here, before it's decoded.
+
+// Meters and centimeters per inch
+const METERS_PER_INCH: f32 = 0.0254;
+const CM_PER_INCH: f32 = 2.54;
+
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+const JPEG_START_OF_IMAGE: [u8; 2] = [0xFF, 0xD8];
+
+// Jpeg markers
+const JPEG_APP0: u8 = 0xE0; // JFIF header
+const JPEG_APP1: u8 = 0xE1; // EXIF data
+const JPEG_START_OF_SCAN: u8 = 0xDA; // Image data follows, so there are no more headers
+const JPEG_END_OF_IMAGE: u8 = 0xD9;
+
+// EXIF (tiff) tags
+const TIFF_X_RESOLUTION: u16 = 0x011A;
+const TIFF_RESOLUTION_UNIT: u16 = 0x0128;
+
+/// The horizontal resolution embedded in a .png or .jpg file, in dots per inch.
+/// None if the file doesn't say, or isn't a .png or .jpg file.
+pub fn embedded_dpi(bytes: &[u8]) -> Option<f32> {
+    let dpi = png_dpi(bytes).or_else(|| jpeg_dpi(bytes))?;
+    (dpi.is_finite() && dpi >= 1.0).then_some(dpi)
+}
+
+//
+// A png file is the signature followed by chunks - a 4 byte length, a 4 byte type, the
+// data, and a 4 byte crc.  pHYs is the physical pixel size, in pixels per meter.
+//
+fn png_dpi(bytes: &[u8]) -> Option<f32> {
+    let mut chunks = bytes.strip_prefix(&PNG_SIGNATURE)?;
+    while chunks.len() >= 12 {
+        let length = u32::from_be_bytes(chunks[0..4].try_into().ok()?) as usize;
+        // Lengths come from the file, so they're added carefully - usize can be 32 bits.
+        let data = chunks.get(8..length.checked_add(8)?)?;
+        match &chunks[4..8] {
+            b"pHYs" if length == 9 && data[8] == 1 => {
+                let pixels_per_meter = u32::from_be_bytes(data[0..4].try_into().ok()?);
+                return Some(pixels_per_meter as f32 * METERS_PER_INCH);
+            }
+            // pHYs has to come before the image data.
+            b"IDAT" | b"IEND" => return None,
+            _ => {}
+        }
+        chunks = chunks.get(length.checked_add(12)?..)?;
+    }
+    None
+}
+
+//
+// A jpeg file is a list of segments - a marker, a 2 byte length (that includes the
+// length bytes), and the data.  EXIF resolutions are used over JFIF ones, which are
+// often just left at a default.
+//
+fn jpeg_dpi(bytes: &[u8]) -> Option<f32> {
+    let mut segments = bytes.strip_prefix(&JPEG_START_OF_IMAGE)?;
+    let mut jfif_dpi = None;
+    let mut exif_dpi = None;
+    while segments.len() >= 4 && segments[0] == 0xFF {
+        let marker = segments[1];
+        if marker == JPEG_START_OF_SCAN || marker == JPEG_END_OF_IMAGE {
+            break;
+        }
+        let length = u16::from_be_bytes([segments[2], segments[3]]) as usize;
+        let data = segments.get(4..2 + length)?;
+        match marker {
+            JPEG_APP0 if data.starts_with(b"JFIF\0") && data.len() >= 12 => {
+                let density = u16::from_be_bytes([data[8], data[9]]) as f32;
+                jfif_dpi = match data[7] {
+                    1 => Some(density),
+                    2 => Some(density * CM_PER_INCH),
+                    _ => None, // Only an aspect ratio
+                };
+            }
+            JPEG_APP1 if data.starts_with(b"Exif\0\0") => {
+                exif_dpi = tiff_dpi(&data[6..]);
+            }
+            _ => {}
+        }
+        segments = segments.get(2 + length..)?;
+    }
+    exif_dpi.or(jfif_dpi)
+}
+
+//
+// EXIF data is a little tiff file.  The resolution tags are in the first image file
+// directory (IFD), which is a count followed by 12 byte entries.
+//
+fn tiff_dpi(tiff: &[u8]) -> Option<f32> {
+    let big_endian = match tiff.get(0..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |offset: usize| -> Option<u16> {
+        let bytes: [u8; 2] = tiff.get(offset..offset.checked_add(2)?)?.try_into().ok()?;
+        Some(match big_endian {
+            true => u16::from_be_bytes(bytes),
+            false => u16::from_le_bytes(bytes),
+        })
+    };
+    let u32_at = |offset: usize| -> Option<u32> {
+        let bytes: [u8; 4] = tiff.get(offset..offset.checked_add(4)?)?.try_into().ok()?;
+        Some(match big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    };
+
+    let ifd = u32_at(4)? as usize;
+    let mut resolution = None;
+    let mut unit = 2; // inches, unless the file says otherwise
+    for index in 0..u16_at(ifd)? as usize {
+        let entry = ifd.checked_add(2 + index * 12)?;
+        match u16_at(entry)? {
+            TIFF_X_RESOLUTION => {
+                // A rational, stored somewhere else in the file.
+                let offset = u32_at(entry + 8)? as usize;
+                let numerator = u32_at(offset)?;
+                let denominator = u32_at(offset.checked_add(4)?)?;
+                if denominator != 0 {
+                    resolution = Some(numerator as f32 / denominator as f32);
+                }
+            }
+            TIFF_RESOLUTION_UNIT => unit = u16_at(entry + 8)?,
+            _ => {}
+        }
+    }
+    match unit {
+        2 => resolution,
+        3 => resolution.map(|per_cm| per_cm * CM_PER_INCH),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod embedded_dpi_should {
+    use super::*;
+
+    fn png_with_chunk(chunk_type: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        png.extend_from_slice(chunk_type);
+        png.extend_from_slice(data);
+        png.extend_from_slice(&[0, 0, 0, 0]); // crc, which isn't checked
+        png
+    }
+
+    #[test]
+    fn read_png_physical_pixel_size() {
+        // 11811 pixels per meter is 300 DPI
+        let mut phys = 11811u32.to_be_bytes().to_vec();
+        phys.extend_from_slice(&11811u32.to_be_bytes());
+        phys.push(1);
+        let dpi = embedded_dpi(&png_with_chunk(b"pHYs", &phys)).unwrap();
+        assert!((dpi - 300.0).abs() < 0.1);
+
+        // Unit 0 is only an aspect ratio
+        *phys.last_mut().unwrap() = 0;
+        assert_eq!(None, embedded_dpi(&png_with_chunk(b"pHYs", &phys)));
+    }
+
+    #[test]
+    fn read_jfif_density() {
+        let mut jpeg = JPEG_START_OF_IMAGE.to_vec();
+        jpeg.extend_from_slice(&[0xFF, JPEG_APP0, 0, 16]);
+        jpeg.extend_from_slice(b"JFIF\0");
+        jpeg.extend_from_slice(&[1, 2, 1, 0, 150, 0, 150, 0, 0]);
+        jpeg.extend_from_slice(&[0xFF, JPEG_END_OF_IMAGE]);
+        assert_eq!(Some(150.0), embedded_dpi(&jpeg));
+    }
+
+    #[test]
+    fn read_exif_resolution() {
+        // Little endian tiff with a two entry IFD at offset 8, and the resolution
+        // rational (600/2 per cm) after it.
+        let mut tiff = b"II".to_vec();
+        tiff.extend_from_slice(&[42, 0, 8, 0, 0, 0, 2, 0]);
+        tiff.extend_from_slice(&[0x1A, 0x01, 5, 0, 1, 0, 0, 0, 38, 0, 0, 0]);
+        tiff.extend_from_slice(&[0x28, 0x01, 3, 0, 1, 0, 0, 0, 3, 0, 0, 0]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+        tiff.extend_from_slice(&[88, 2, 0, 0, 2, 0, 0, 0]);
+
+        let mut jpeg = JPEG_START_OF_IMAGE.to_vec();
+        let length = (2 + 6 + tiff.len()) as u16;
+        jpeg.extend_from_slice(&[0xFF, JPEG_APP1]);
+        jpeg.extend_from_slice(&length.to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        let dpi = embedded_dpi(&jpeg).unwrap();
+        assert!((dpi - 762.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn ignore_files_without_a_resolution() {
+        assert_eq!(None, embedded_dpi(include_bytes!("../assets/tux.svg")));
+        assert_eq!(None, embedded_dpi(&png_with_chunk(b"IDAT", &[0; 4])));
+        assert_eq!(None, embedded_dpi(&[]));
+    }
+
+    #[test]
+    fn ignore_lengths_past_the_end_of_the_file() {
+        let mut png = png_with_chunk(b"tEXt", &[0; 4]);
+        png[8..12].copy_from_slice(&0xFFFF_FFFFu32.to_be_bytes());
+        assert_eq!(None, embedded_dpi(&png));
+
+        // An IFD and resolution offset at the very end of a 32 bit address space
+        let mut tiff = b"MM".to_vec();
+        tiff.extend_from_slice(&[0, 42, 0xFF, 0xFF, 0xFF, 0xFE]);
+        assert_eq!(None, tiff_dpi(&tiff));
+        let mut tiff = b"MM".to_vec();
+        tiff.extend_from_slice(&[0, 42, 0, 0, 0, 8, 0, 1]);
+        tiff.extend_from_slice(&[0x01, 0x1A, 0, 5, 0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFE]);
+        assert_eq!(None, tiff_dpi(&tiff));
+    }
+}
@@ -1,52 +1,153 @@
 use crate::loaded_image::*;
 use crate::Hsla;
+use nalgebra::{vector, Matrix3};
 use std::cmp::Ordering;
 
 pub type PixelMutator = Box<dyn Fn(&egui::Color32) -> egui::Color32>;
 
-pub fn blue_to_red() -> PixelMutator {
-    Hsla::calc_hsla_transform(
-        egui::Color32::from_rgb(0, 143, 190),
-        egui::Color32::from_rgb(192, 0, 0),
-    )
+pub fn blue_to_color(target: egui::Color32) -> PixelMutator {
+    Hsla::calc_hsla_transform(egui::Color32::from_rgb(0, 143, 190), target)
 }
 
-pub fn blue_to_dgreen() -> PixelMutator {
-    Hsla::calc_hsla_transform(
-        egui::Color32::from_rgb(0, 143, 190),
-        egui::Color32::from_rgb(0, 128, 64),
-    )
+//
+// Give a t-shirt a mottled, heathered look.  Heathered fabric is woven from fibres
+// of slightly different shades, so each short horizontal run of pixels is lightened
+// or darkened by a small pseudo random amount.  The "random" amounts come from a hash
+// so the texture is the same every run.
+//
+// The photo's background shouldn't be heathered.  The blue t-shirt photo is used to
+// tell the two apart - the fabric is saturated and the background isn't.
+//
+pub fn heather(
+    input: &LoadedImage,
+    blue_shirt: &LoadedImage,
+    name: impl Into<String>,
+) -> LoadedImage {
+    const FIBRE_LENGTH: usize = 3;
+    const MAX_SHIFT: i32 = 18;
+    let size = *input.size_as_array();
+    let pixels = input
+        .pixels()
+        .iter()
+        .zip(blue_shirt.pixels())
+        .enumerate()
+        .map(|(i, (p, blue))| {
+            let x = i % size[0];
+            let y = i / size[0];
+            let fibre = (y * size[0] + x / FIBRE_LENGTH) as u32;
+            let mut hash = fibre.wrapping_mul(0x9e37_79b1);
+            hash ^= hash >> 15;
+            hash = hash.wrapping_mul(0x85eb_ca6b);
+            hash ^= hash >> 13;
+            let shift = (hash % (2 * MAX_SHIFT as u32 + 1)) as i32 - MAX_SHIFT;
+            let shift = shift * i32::from(Hsla::from(blue).s) / 1024;
+
+            // Pixels are premultiplied, so scale the shift by alpha
+            let alpha = i32::from(p.a());
+            let adjust = |c: u8| (i32::from(c) + shift * alpha / 255).clamp(0, alpha) as u8;
+            egui::Color32::from_rgba_premultiplied(
+                adjust(p.r()),
+                adjust(p.g()),
+                adjust(p.b()),
+                p.a(),
+            )
+        })
+        .collect();
+    load_image_from_pixels(pixels, size, name)
 }
 
-pub fn blue_to_ddgreen() -> PixelMutator {
-    Hsla::calc_hsla_transform(
-        egui::Color32::from_rgb(0, 143, 190),
-        egui::Color32::from_rgb(0, 64, 32),
-    )
+/// The biggest difference between two colors in any one channel, from 0 to 255
+pub fn color_distance(c0: egui::Color32, c1: egui::Color32) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).unsigned_abs();
+    d(c0.r(), c1.r())
+        .max(d(c0.g(), c1.g()))
+        .max(d(c0.b(), c1.b()))
 }
 
-pub fn blue_to_dblue() -> PixelMutator {
-    Hsla::calc_hsla_transform(
-        egui::Color32::from_rgb(0, 143, 190),
-        egui::Color32::from_rgb(0, 143 / 2, 190 / 2),
-    )
+/// The color of a pixel printed with solid ink.  Pixels are premultiplied in linear
+/// space, so the alpha is divided back out there.  Premultiplying rounds, so a channel
+/// that premultiplies to the same value as 255 is taken to be 255.
+pub fn make_opaque(input: &egui::Color32) -> egui::Color32 {
+    match input.a() {
+        0 => egui::Color32::TRANSPARENT,
+        alpha => {
+            let full = egui::Color32::from_rgba_unmultiplied(255, 255, 255, alpha).r();
+            let snap = |c: u8, premultiplied: u8| if premultiplied >= full { 255 } else { c };
+            let [r, g, b, _] = input.to_srgba_unmultiplied();
+            egui::Color32::from_rgb(snap(r, input.r()), snap(g, input.g()), snap(b, input.b()))
+        }
+    }
 }
 
-pub fn blue_to_burg() -> PixelMutator {
-    Hsla::calc_hsla_transform(
-        egui::Color32::from_rgb(0, 143, 190),
-        egui::Color32::from_rgb(96, 0, 0),
-    )
+/// How the partial transparency fix decides which pixels to keep
+#[derive(Debug, PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AlphaFixSettings {
+    /// Pixels at least this opaque, in percent, are printed
+    pub threshold: u32,
+    /// Spread the rounding error to neighbouring pixels, so fades become speckles
+    /// instead of a hard edge.
+    pub dither: bool,
 }
 
-pub fn correct_alpha_for_tshirt(input: &egui::Color32) -> egui::Color32 {
-    if input.a() < 255 {
-        egui::Color32::TRANSPARENT
-    } else {
-        egui::Color32::from_rgb(input.r(), input.g(), input.b())
+impl Default for AlphaFixSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 50,
+            dither: false,
+        }
     }
 }
 
+//
+// Map every alpha to 0 or 255.  Without dithering, pixels at or above the threshold are
+// kept and the rest dropped.  With dithering, the difference between each partially
+// transparent pixel's alpha and what was printed is pushed on to the partially
+// transparent pixels to its right and below (Floyd-Steinberg error diffusion), so a
+// fade keeps about the same amount of ink.
+//
+pub fn correct_alpha(
+    input: &LoadedImage,
+    fix: &AlphaFixSettings,
+    name: impl Into<String>,
+) -> LoadedImage {
+    let [width, height] = *input.size_as_array();
+    let in_pixels = input.pixels();
+    let threshold = fix.threshold.clamp(1, 100) as f32 / 100.0;
+    let partial = |i: usize| in_pixels[i].a() != 0 && in_pixels[i].a() != 255;
+    let mut error = vec![0.0f32; width * height];
+    let mut pixels = Vec::with_capacity(width * height);
+    for i in 0..width * height {
+        let pixel = &in_pixels[i];
+        if !partial(i) {
+            pixels.push(make_opaque(pixel));
+            continue;
+        }
+        let alpha = pixel.a() as f32 / 255.0 + error[i];
+        let printed = alpha >= threshold;
+        pixels.push(match printed {
+            true => make_opaque(pixel),
+            false => egui::Color32::TRANSPARENT,
+        });
+        if fix.dither {
+            let remainder = alpha - if printed { 1.0 } else { 0.0 };
+            let (x, y) = (i % width, i / width);
+            let mut spread = |nx: usize, ny: usize, weight: f32| {
+                let n = ny * width + nx;
+                if nx < width && ny < height && partial(n) {
+                    error[n] += remainder * weight;
+                }
+            };
+            spread(x + 1, y, 7.0 / 16.0);
+            if x > 0 {
+                spread(x - 1, y + 1, 3.0 / 16.0);
+            }
+            spread(x, y + 1, 5.0 / 16.0);
+            spread(x + 1, y + 1, 1.0 / 16.0);
+        }
+    }
+    load_image_from_existing_size(input, pixels, name)
+}
+
 pub fn flag_alpha_for_shirt(input: &egui::Color32) -> egui::Color32 {
     let not_binary = input.a() != 0 && input.a() != 255;
     if not_binary {
@@ -92,6 +193,87 @@ pub fn opaque_to_mask(input: &egui::Color32) -> egui::Color32 {
     }
 }
 
+// Sample an image at (u, v), where (0,0) is the top left corner of the image and (1,1)
+// is the bottom right, using bilinear filtering.
+//
+fn sample_bilinear(image: &LoadedImage, u: f32, v: f32) -> egui::Color32 {
+    let [xsize, ysize] = *image.size_as_array();
+    let x = (u * xsize as f32 - 0.5).clamp(0.0, (xsize - 1) as f32);
+    let y = (v * ysize as f32 - 0.5).clamp(0.0, (ysize - 1) as f32);
+    let (x0, y0) = (x as usize, y as usize);
+    let (x1, y1) = ((x0 + 1).min(xsize - 1), (y0 + 1).min(ysize - 1));
+    let (xf, yf) = (x - x0 as f32, y - y0 as f32);
+
+    let pixels = image.pixels();
+    let corners = [
+        (pixels[x0 + y0 * xsize], (1.0 - xf) * (1.0 - yf)),
+        (pixels[x1 + y0 * xsize], xf * (1.0 - yf)),
+        (pixels[x0 + y1 * xsize], (1.0 - xf) * yf),
+        (pixels[x1 + y1 * xsize], xf * yf),
+    ];
+    let channel = |c: usize| -> u8 {
+        let sum: f32 = corners
+            .iter()
+            .map(|(p, weight)| p.to_array()[c] as f32 * weight)
+            .sum();
+        sum.round() as u8
+    };
+    egui::Color32::from_rgba_premultiplied(channel(0), channel(1), channel(2), channel(3))
+}
+
+// Draw premultiplied color "top" over "bottom"
+//
+fn blend_over(top: egui::Color32, bottom: egui::Color32) -> egui::Color32 {
+    let uncovered = 255 - top.a() as u32;
+    let channel = |t: u8, b: u8| -> u8 { (t as u32 + b as u32 * uncovered / 255) as u8 };
+    egui::Color32::from_rgba_premultiplied(
+        channel(top.r(), bottom.r()),
+        channel(top.g(), bottom.g()),
+        channel(top.b(), bottom.b()),
+        channel(top.a(), bottom.a()),
+    )
+}
+
+//
+// Render the artwork on the t-shirt as a single image that's xsize pixels wide.
+//
+// art_to_tshirt maps the artwork, where (0,0) is the top corner of the artwork and
+// (1,1) is the bottom corner, into t-shirt space.  The t-shirt images are small, so
+// the t-shirt is scaled up with bilinear filtering.
+//
+pub fn mockup_on_shirt(
+    shirt: &LoadedImage,
+    art: &LoadedImage,
+    art_to_tshirt: Matrix3<f32>,
+    xsize: usize,
+) -> LoadedImage {
+    let ysize = (xsize as f32 * shirt.size().y / shirt.size().x) as usize;
+    // Art squashed flat has nowhere to be drawn, so only the t-shirt is shown.
+    let tshirt_to_art = art_to_tshirt.try_inverse();
+    let [art_xsize, art_ysize] = *art.size_as_array();
+
+    let pixels = (0..xsize * ysize)
+        .map(|index| {
+            let u = ((index % xsize) as f32 + 0.5) / xsize as f32;
+            let v = ((index / xsize) as f32 + 0.5) / ysize as f32;
+            let shirt_pixel = sample_bilinear(shirt, u, v);
+
+            let art_location = tshirt_to_art.map(|to_art| to_art * vector![u, v, 1.0]);
+            let on_art = art_location.filter(|location| {
+                (0.0..1.0).contains(&location.x) && (0.0..1.0).contains(&location.y)
+            });
+            if let Some(art_location) = on_art {
+                let ax = (art_location.x * art_xsize as f32) as usize;
+                let ay = (art_location.y * art_ysize as f32) as usize;
+                blend_over(art.pixels()[ax + ay * art_xsize], shirt_pixel)
+            } else {
+                shirt_pixel
+            }
+        })
+        .collect();
+    load_image_from_pixels(pixels, [xsize, ysize], "mockup")
+}
+
 #[derive(Eq, Clone)]
 pub struct HotSpot {
     pub strength: u8,
@@ -325,37 +507,34 @@ pub fn expand_good(input: &[ThinLineCategory], xdim: i32, ydim: i32) -> Vec<Thin
         .collect()
 }
 
-async fn thin_line_detect(
+async fn thin_line_categories(
     input: &Vec<egui::Color32>,
     size: [usize; 2],
     min_pixels: usize,
-    ctx: &egui::Context,
-) -> Vec<egui::Color32> {
+) -> Vec<ThinLineCategory> {
     let xdim = size[0] as i32;
     let ydim = size[1] as i32;
-    let one_milli = std::time::Duration::from_millis(1);
-
     let mut output: Vec<u32> = vec![0; input.len()];
 
     let mut thin_line_state: ThinLineState<'_, RBS> =
         ThinLineState::new(input, &mut output, min_pixels, xdim);
 
     thin_line_vertical(&mut thin_line_state, xdim, ydim);
-    crate::async_tasks::context_switch(ctx).await;
+    crate::async_tasks::yield_now().await;
     thin_line_horizontal(&mut thin_line_state, xdim, ydim);
-    crate::async_tasks::context_switch(ctx).await;
+    crate::async_tasks::yield_now().await;
     thin_line_diag::<256, 256>(&mut thin_line_state, xdim, ydim);
-    crate::async_tasks::context_switch(ctx).await;
+    crate::async_tasks::yield_now().await;
     thin_line_diag::<256, -256>(&mut thin_line_state, xdim, ydim);
-    crate::async_tasks::context_switch(ctx).await;
+    crate::async_tasks::yield_now().await;
     thin_line_diag::<256, 128>(&mut thin_line_state, xdim, ydim);
-    crate::async_tasks::context_switch(ctx).await;
+    crate::async_tasks::yield_now().await;
     thin_line_diag::<256, -128>(&mut thin_line_state, xdim, ydim);
-    crate::async_tasks::context_switch(ctx).await;
+    crate::async_tasks::yield_now().await;
     thin_line_diag::<128, 256>(&mut thin_line_state, xdim, ydim);
-    crate::async_tasks::context_switch(ctx).await;
+    crate::async_tasks::yield_now().await;
     thin_line_diag::<-128, 256>(&mut thin_line_state, xdim, ydim);
-    crate::async_tasks::context_switch(ctx).await;
+    crate::async_tasks::yield_now().await;
 
     let mut thin_line_state: Vec<_> = input
         .iter()
@@ -370,14 +549,22 @@ async fn thin_line_detect(
             }
         })
         .collect();
-    async_std::task::sleep(one_milli).await;
+    crate::async_tasks::yield_now().await;
 
     let num_expansions = min_pixels * 2;
     for _ in 0..num_expansions {
         thin_line_state = expand_good(&thin_line_state, xdim, ydim);
-        async_std::task::sleep(one_milli).await;
+        crate::async_tasks::yield_now().await;
     }
+    thin_line_state
+}
 
+async fn thin_line_detect(
+    input: &Vec<egui::Color32>,
+    size: [usize; 2],
+    min_pixels: usize,
+) -> Vec<egui::Color32> {
+    let thin_line_state = thin_line_categories(input, size, min_pixels).await;
     input
         .iter()
         .zip(thin_line_state)
@@ -391,13 +578,63 @@ async fn thin_line_detect(
         .collect()
 }
 
-pub async fn flag_thin_lines(
+pub async fn flag_thin_lines(input: &LoadedImage, min_pixels: usize) -> LoadedImage {
+    let output = thin_line_detect(input.pixels(), *input.size_as_array(), min_pixels).await;
+    load_image_from_pixels(output, *input.size_as_array(), "thin_lines")
+}
+
+//
+// Thicken the lines that are too thin to print.  The problem pixels are grown out into
+// the transparent pixels around them, one pixel per pass, until a one pixel line is
+// wider than min_pixels.  Grown pixels take the color of the pixel they grew from, and
+// lines that are already thick enough are left alone.
+//
+pub async fn thicken_thin_lines(
     input: &LoadedImage,
-    ctx: &egui::Context,
     min_pixels: usize,
+    name: impl Into<String>,
 ) -> LoadedImage {
-    let output = thin_line_detect(input.pixels(), *input.size_as_array(), min_pixels, ctx).await;
-    return load_image_from_pixels(output, *input.size_as_array(), "thin_lines", ctx);
+    let size = *input.size_as_array();
+    let [xdim, ydim] = size;
+    let categories = thin_line_categories(input.pixels(), size, min_pixels).await;
+    let mut pixels = input.pixels().clone();
+    let mut grown: Vec<bool> = categories
+        .iter()
+        .map(|c| *c == ThinLineCategory::OpaqueProblem)
+        .collect();
+
+    for pass in 0..min_pixels.div_ceil(2) {
+        // Alternating between growing into the 4 and the 8 neighbours keeps the
+        // thickened line roughly round at the ends.
+        let diagonals = pass % 2 == 1;
+        let mut next_grown = grown.clone();
+        for y in 0..ydim {
+            for x in 0..xdim {
+                let index = x + y * xdim;
+                if grown[index] || categories[index] != ThinLineCategory::Transparent {
+                    continue;
+                }
+                let neighbours = (-1..=1i32)
+                    .flat_map(|yd| (-1..=1i32).map(move |xd| (xd, yd)))
+                    .filter(|(xd, yd)| (*xd == 0) != (*yd == 0) || (diagonals && *xd != 0));
+                for (xd, yd) in neighbours {
+                    let (xs, ys) = (x as i32 + xd, y as i32 + yd);
+                    if xs < 0 || ys < 0 || xs >= xdim as i32 || ys >= ydim as i32 {
+                        continue;
+                    }
+                    let source = xs as usize + ys as usize * xdim;
+                    if grown[source] {
+                        pixels[index] = pixels[source];
+                        next_grown[index] = true;
+                        break;
+                    }
+                }
+            }
+        }
+        grown = next_grown;
+        crate::async_tasks::yield_now().await;
+    }
+    load_image_from_existing_size(input, pixels, name)
 }
 
 pub fn count_diffs(in0: &LoadedImage, in1: &LoadedImage) -> usize {
@@ -421,3 +658,181 @@ pub fn compute_percent_diff(in0: &LoadedImage, in1: &LoadedImage) -> u32 {
         percent_thin_line
     }
 }
+
+#[cfg(test)]
+mod image_utils_should {
+    use super::*;
+    use egui::Color32;
+
+    #[test]
+    fn make_opaque_divides_out_the_alpha() {
+        assert_eq!(Color32::TRANSPARENT, make_opaque(&Color32::TRANSPARENT));
+        assert_eq!(Color32::RED, make_opaque(&Color32::RED));
+        for alpha in [32, 100, 128, 200, 254] {
+            let red = Color32::from_rgba_unmultiplied(255, 0, 0, alpha);
+            assert_eq!(Color32::RED, make_opaque(&red), "alpha {}", alpha);
+            let teal = Color32::from_rgba_unmultiplied(40, 150, 130, alpha);
+            let opaque = make_opaque(&teal);
+            let close = |a: u8, b: u8| (a as i32 - b as i32).abs() <= 2;
+            assert!(
+                close(40, opaque.r()) && close(150, opaque.g()) && close(130, opaque.b()),
+                "alpha {} {:?}",
+                alpha,
+                opaque
+            );
+        }
+    }
+
+    #[test]
+    fn mockup_art_squashed_flat_as_just_the_shirt() {
+        let shirt = load_image_from_pixels(vec![Color32::BLUE; 16], [4, 4], "shirt");
+        let art = load_image_from_pixels(vec![Color32::RED; 16], [4, 4], "art");
+        let flat = Matrix3::new(0.5, 0.0, 0.25, 0.0, 0.0, 0.25, 0.0, 0.0, 1.0);
+        let mockup = mockup_on_shirt(&shirt, &art, flat, 8);
+        assert!(mockup.pixels().iter().all(|p| *p == Color32::BLUE));
+    }
+
+    // A 64 wide fade from transparent to 3/4 opaque white, with a solid block on
+    // the right.
+    fn fade() -> LoadedImage {
+        let fade = |x, _| match x {
+            0..=47 => Color32::from_white_alpha((x * 4) as u8),
+            _ => Color32::WHITE,
+        };
+        load_image_from_function([64, 16], fade, "fade")
+    }
+
+    fn opaque_in(image: &LoadedImage, columns: std::ops::Range<usize>) -> usize {
+        image
+            .pixels()
+            .iter()
+            .enumerate()
+            .filter(|(i, p)| columns.contains(&(i % 64)) && p.a() == 255)
+            .count()
+    }
+
+    #[test]
+    fn keep_pixels_above_the_alpha_threshold() {
+        let fixed = correct_alpha(&fade(), &AlphaFixSettings::default(), "fixed");
+        assert!(fixed.pixels().iter().all(|p| p.a() == 0 || p.a() == 255));
+        // Alpha 128 and up is kept, solid white, and the rest is dropped
+        assert_eq!(Color32::TRANSPARENT, fixed.pixels()[31]);
+        assert_eq!(Color32::WHITE, fixed.pixels()[32]);
+        assert_eq!(16 * 32, opaque_in(&fixed, 0..64));
+
+        let strict = AlphaFixSettings {
+            threshold: 100,
+            dither: false,
+        };
+        let fixed = correct_alpha(&fade(), &strict, "fixed");
+        assert_eq!(16 * 16, opaque_in(&fixed, 0..64));
+    }
+
+    #[test]
+    fn dither_fades_into_speckles() {
+        let dithered = AlphaFixSettings {
+            threshold: 50,
+            dither: true,
+        };
+        let fixed = correct_alpha(&fade(), &dithered, "fixed");
+        assert!(fixed.pixels().iter().all(|p| p.a() == 0 || p.a() == 255));
+        // The fade keeps about as much ink as it had alpha: around a quarter of the
+        // faint end and half of the heavy end.
+        let faint = opaque_in(&fixed, 8..24);
+        let heavy = opaque_in(&fixed, 24..48);
+        assert!((40..90).contains(&faint), "faint {}", faint);
+        assert!((180..250).contains(&heavy), "heavy {}", heavy);
+        assert_eq!(16 * 16, opaque_in(&fixed, 48..64));
+    }
+
+    const XDIM: usize = 32;
+    const YDIM: usize = 32;
+
+    // A transparent image with a 1 pixel wide vertical line at x=4 and a solid
+    // 12 x 12 block in the lower right.
+    fn line_and_block() -> LoadedImage {
+        let line_and_block = |x, y| {
+            let on_line = x == 4 && (2..30).contains(&y);
+            let on_block = (16..28).contains(&x) && (16..28).contains(&y);
+            if on_line || on_block {
+                Color32::WHITE
+            } else {
+                Color32::TRANSPARENT
+            }
+        };
+        load_image_from_function([XDIM, YDIM], line_and_block, "line_and_block")
+    }
+
+    #[test]
+    fn flag_thin_lines_but_not_thick_areas() {
+        let art = line_and_block();
+        let flagged = async_std::task::block_on(flag_thin_lines(&art, 3));
+
+        let flagged_at = |x: usize, y: usize| flagged.pixels()[x + y * XDIM] != Color32::WHITE;
+        assert!(flagged_at(4, 10));
+        assert!(flagged_at(4, 20));
+        assert!(!flagged_at(20, 20));
+        assert!(!flagged_at(16, 16));
+    }
+
+    #[test]
+    fn thicken_thin_lines_but_not_thick_areas() {
+        let art = line_and_block();
+        let fixed = async_std::task::block_on(thicken_thin_lines(&art, 3, "fixed"));
+
+        let at = |x: usize, y: usize| fixed.pixels()[x + y * XDIM];
+        // The line is now 5 pixels wide, in its own color
+        assert_eq!(Color32::WHITE, at(2, 10));
+        assert_eq!(Color32::WHITE, at(6, 10));
+        assert_eq!(Color32::TRANSPARENT, at(1, 10));
+        assert_eq!(Color32::TRANSPARENT, at(7, 10));
+        // The block didn't grow
+        assert_eq!(Color32::TRANSPARENT, at(15, 20));
+        assert_eq!(Color32::TRANSPARENT, at(28, 20));
+
+        let flagged = async_std::task::block_on(flag_thin_lines(&fixed, 3));
+        assert_eq!(0, count_diffs(&flagged, &fixed));
+    }
+
+    #[test]
+    fn compute_percent_diffs_rounding_small_ones_up() {
+        let art = line_and_block();
+        let recolored = load_image_from_existing_image(
+            &art,
+            &|p: &Color32| {
+                if *p == Color32::WHITE {
+                    Color32::BLACK
+                } else {
+                    *p
+                }
+            },
+            "changed",
+        );
+        assert_eq!(0, compute_percent_diff(&art, &art));
+        // 28 + 144 changed pixels out of 1024 is 16.8%
+        assert_eq!(16, compute_percent_diff(&art, &recolored));
+
+        let mut pixels = art.pixels().clone();
+        pixels[0] = Color32::RED;
+        let tiny_change = load_image_from_pixels(pixels, [XDIM, YDIM], "tiny_change");
+        assert_eq!(1, compute_percent_diff(&art, &tiny_change));
+    }
+
+    #[test]
+    fn compute_opaque_and_partial_transparency_percents() {
+        let pixels = vec![
+            Color32::TRANSPARENT,
+            Color32::TRANSPARENT,
+            Color32::from_rgba_premultiplied(10, 10, 10, 128),
+            Color32::WHITE,
+        ];
+        assert_eq!(50, compute_percent_opaque(&pixels));
+        assert_eq!(25, compute_bad_tpixels(&pixels));
+    }
+
+    #[test]
+    fn build_one_heat_map_pixel_per_64x64_block() {
+        let heat_map = heat_map_from_image(&line_and_block(), "heat_map");
+        assert_eq!([1, 1], *heat_map.size_as_array());
+    }
+}
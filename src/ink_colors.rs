@@ -0,0 +1,262 @@
+//! Ink color counting
+//!
+//! Screen printing needs a screen (and an ink) for every color in the art, so the
+//! cost of a print goes up with the number of colors.  Art rarely has exactly N
+//! colors in it though - anti-aliased edges blend neighbouring inks together, and
+//! compression adds noise.  The opaque pixels are clustered into inks instead:
+//! similar colors are merged, and clusters too small to be a real ink are treated as
+//! noise and printed with the nearest real ink.
+
+use crate::loaded_image::*;
+use std::sync::OnceLock;
+
+// Colors closer than this (in RGB) are the same ink
+const MERGE_DISTANCE: i32 = 48;
+// Clusters with less than this fraction of the opaque pixels are anti-aliasing or
+// compression noise, not inks.
+const MIN_INK_FRACTION: f32 = 0.005;
+// Photos would otherwise end up with thousands of clusters.  Past this many, colors
+// are merged into the nearest cluster no matter how far away it is.
+const MAX_CLUSTERS: usize = 64;
+// Pixels are binned by the top 5 bits of each channel before clustering
+const BIN_SHIFT: u32 = 3;
+const BINS: usize = 1 << (3 * (8 - BIN_SHIFT));
+// Label for pixels that aren't printed
+const NO_INK: u8 = u8::MAX;
+
+/// One ink color needed to print the art
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct InkColor {
+    pub color: egui::Color32,
+    /// How much of the art is printed with this ink, as a percentage of the opaque
+    /// pixels
+    pub percent: u32,
+}
+
+/// The inks needed to print a piece of art, and where each one is used
+pub struct InkPalette {
+    inks: Vec<InkColor>,
+    // The ink each pixel is printed with
+    labels: Vec<u8>,
+    art: LoadedImage,
+    // Created the first time the tool shows an ink, since most never get looked at.
+    highlights: Vec<OnceLock<LoadedImage>>,
+}
+
+// A group of similar colors
+#[derive(Default)]
+struct Cluster {
+    seed: [i32; 3],
+    sum: [u64; 3],
+    count: u64,
+}
+
+impl Cluster {
+    fn mean(&self) -> [i32; 3] {
+        let count = self.count.max(1);
+        self.sum.map(|sum| (sum / count) as i32)
+    }
+}
+
+fn bin_of(pixel: &egui::Color32) -> usize {
+    let [r, g, b, _] = pixel.to_array().map(|c| (c >> BIN_SHIFT) as usize);
+    (r << 10) | (g << 5) | b
+}
+
+fn distance_squared(a: [i32; 3], b: [i32; 3]) -> i32 {
+    (0..3).map(|i| (a[i] - b[i]) * (a[i] - b[i])).sum()
+}
+
+fn nearest(colors: &[[i32; 3]], color: [i32; 3]) -> Option<usize> {
+    (0..colors.len()).min_by_key(|&index| distance_squared(colors[index], color))
+}
+
+impl InkPalette {
+    pub fn new(art: &LoadedImage) -> Self {
+        //
+        // Histogram the opaque pixels.  Partially transparent pixels don't get
+        // printed, so they don't need an ink.
+        //
+        let mut bins: Vec<Cluster> = (0..BINS).map(|_| Cluster::default()).collect();
+        for pixel in art.pixels().iter().filter(|pixel| pixel.a() == 255) {
+            let bin = &mut bins[bin_of(pixel)];
+            for (sum, c) in bin.sum.iter_mut().zip(pixel.to_array()) {
+                *sum += c as u64;
+            }
+            bin.count += 1;
+        }
+        let opaque: u64 = bins.iter().map(|bin| bin.count).sum();
+
+        //
+        // Greedily cluster the bins, most common colors first, so each cluster is
+        // seeded by the color that's most likely to be the real ink.
+        //
+        let mut order: Vec<usize> = (0..BINS).filter(|&bin| bins[bin].count > 0).collect();
+        order.sort_by_key(|&bin| std::cmp::Reverse(bins[bin].count));
+        let mut clusters: Vec<Cluster> = Vec::new();
+        for bin in order {
+            let color = bins[bin].mean();
+            let seeds: Vec<[i32; 3]> = clusters.iter().map(|cluster| cluster.seed).collect();
+            let target = match nearest(&seeds, color) {
+                Some(index)
+                    if clusters.len() >= MAX_CLUSTERS
+                        || distance_squared(seeds[index], color)
+                            <= MERGE_DISTANCE * MERGE_DISTANCE =>
+                {
+                    index
+                }
+                _ => {
+                    clusters.push(Cluster {
+                        seed: color,
+                        ..Default::default()
+                    });
+                    clusters.len() - 1
+                }
+            };
+            let cluster = &mut clusters[target];
+            for i in 0..3 {
+                cluster.sum[i] += bins[bin].sum[i];
+            }
+            cluster.count += bins[bin].count;
+        }
+
+        //
+        // Drop the noise, and send each bin's pixels to the nearest real ink.
+        //
+        let min_count = (opaque as f32 * MIN_INK_FRACTION).ceil() as u64;
+        clusters.retain(|cluster| cluster.count >= min_count.max(1));
+        let ink_colors: Vec<[i32; 3]> = clusters.iter().map(Cluster::mean).collect();
+        let bin_to_ink: Vec<u8> = bins
+            .iter()
+            .map(|bin| match nearest(&ink_colors, bin.mean()) {
+                Some(index) if bin.count > 0 => index as u8,
+                _ => NO_INK,
+            })
+            .collect();
+        let labels: Vec<u8> = art
+            .pixels()
+            .iter()
+            .map(|pixel| match pixel.a() {
+                255 => bin_to_ink[bin_of(pixel)],
+                _ => NO_INK,
+            })
+            .collect();
+
+        // Report the inks by how much of the art they cover, including the noise
+        // that's printed with them.
+        let mut counts = vec![0u64; ink_colors.len()];
+        for label in labels.iter().filter(|label| **label != NO_INK) {
+            counts[*label as usize] += 1;
+        }
+        let mut inks: Vec<(u64, InkColor)> = ink_colors
+            .iter()
+            .zip(counts)
+            .map(|([r, g, b], count)| {
+                let color = egui::Color32::from_rgb(*r as u8, *g as u8, *b as u8);
+                let percent = (100 * count / opaque.max(1)) as u32;
+                (count, InkColor { color, percent })
+            })
+            .collect();
+        // Sort the labels along with the inks
+        let mut by_count: Vec<usize> = (0..inks.len()).collect();
+        by_count.sort_by_key(|&index| std::cmp::Reverse(inks[index].0));
+        let mut relabel = vec![NO_INK; 256];
+        for (new_index, old_index) in by_count.iter().enumerate() {
+            relabel[*old_index] = new_index as u8;
+        }
+        let labels = labels
+            .iter()
+            .map(|label| relabel[*label as usize])
+            .collect();
+        inks.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+        let inks: Vec<InkColor> = inks.into_iter().map(|(_, ink)| ink).collect();
+
+        Self {
+            highlights: inks.iter().map(|_| OnceLock::new()).collect(),
+            inks,
+            labels,
+            art: art.clone(),
+        }
+    }
+
+    /// The inks, most used first
+    pub fn inks(&self) -> &[InkColor] {
+        &self.inks
+    }
+
+    /// The art with one ink's pixels shown as they are, and the rest of the art
+    /// dimmed.
+    pub fn highlight(&self, ink: usize) -> &LoadedImage {
+        self.highlights[ink].get_or_init(|| {
+            let dimmed = egui::Color32::from_rgba_unmultiplied(128, 128, 128, 64);
+            let pixels = self
+                .art
+                .pixels()
+                .iter()
+                .zip(&self.labels)
+                .map(|(pixel, label)| match *label {
+                    NO_INK => egui::Color32::TRANSPARENT,
+                    label if label as usize == ink => *pixel,
+                    _ => dimmed,
+                })
+                .collect();
+            load_image_from_pixels(
+                pixels,
+                *self.art.size_as_array(),
+                format!("ink_highlight_{}", ink),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod ink_palette_should {
+    use super::*;
+
+    const RED: egui::Color32 = egui::Color32::from_rgb(200, 30, 30);
+    const BLUE: egui::Color32 = egui::Color32::from_rgb(20, 40, 220);
+
+    // Red on the left, blue on the right, with an anti-aliased column between them
+    // and a bit of noise on every pixel.
+    fn two_color_art() -> LoadedImage {
+        let two_colors = |x: usize, y: usize| {
+            let noise = ((y * 400 + x) % 7) as u8;
+            let base = match x {
+                0..=149 => RED,
+                150 => egui::Color32::from_rgb(110, 35, 125),
+                _ => BLUE,
+            };
+            egui::Color32::from_rgb(base.r() + noise, base.g() + noise, base.b() - noise)
+        };
+        load_image_from_function([400, 50], two_colors, "two_colors")
+    }
+
+    #[test]
+    fn ignore_anti_aliasing_and_noise() {
+        let palette = InkPalette::new(&two_color_art());
+        assert_eq!(2, palette.inks().len());
+        for (ink, expected) in palette.inks().iter().zip([BLUE, RED]) {
+            assert!((ink.color.r() as i32 - expected.r() as i32).abs() < 8);
+            assert!((ink.color.b() as i32 - expected.b() as i32).abs() < 8);
+        }
+        assert_eq!(62, palette.inks()[0].percent);
+        assert_eq!(37, palette.inks()[1].percent);
+    }
+
+    #[test]
+    fn skip_pixels_that_wont_be_printed() {
+        let pixels = vec![egui::Color32::from_rgba_unmultiplied(200, 0, 0, 128); 100];
+        let palette = InkPalette::new(&load_image_from_pixels(pixels, [10, 10], "see_through"));
+        assert!(palette.inks().is_empty());
+    }
+
+    #[test]
+    fn highlight_one_ink_at_a_time() {
+        let art = two_color_art();
+        let palette = InkPalette::new(&art);
+        let blue = palette.highlight(0);
+        assert_eq!(art.size(), blue.size());
+        assert_eq!(art.pixels()[399], blue.pixels()[399]);
+        assert_ne!(art.pixels()[0], blue.pixels()[0]);
+    }
+}
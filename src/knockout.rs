@@ -0,0 +1,111 @@
+//! Knock out the shirt color
+//!
+//! Where the art uses the same color as the shirt, there's no need to print it - the
+//! shirt can show through instead.  It's less ink, a softer print, and one less
+//! screen for a screen printer.
+
+use crate::image_utils::*;
+use crate::loaded_image::*;
+use std::sync::Mutex;
+
+/// The art with the shirt color knocked out
+#[derive(Clone)]
+pub struct KnockedOut {
+    pub image: LoadedImage,
+    /// Percentage of the image that's still printed
+    pub opaque_percent: u32,
+}
+
+/// The art with every pixel within tolerance (0 to 255, per color channel) of the
+/// shirt color made transparent
+pub fn knock_out(
+    art: &LoadedImage,
+    shirt_color: egui::Color32,
+    tolerance: u32,
+    name: impl Into<String>,
+) -> LoadedImage {
+    load_image_from_existing_image(
+        art,
+        &|pixel: &egui::Color32| {
+            if pixel.a() > 0 && color_distance(make_opaque(pixel), shirt_color) <= tolerance {
+                egui::Color32::TRANSPARENT
+            } else {
+                *pixel
+            }
+        },
+        name,
+    )
+}
+
+/// Previews of the art with the shirt color knocked out
+pub struct KnockoutPreview {
+    art: LoadedImage,
+    // The last shirt color and tolerance previewed
+    preview: Mutex<Option<(egui::Color32, u32, KnockedOut)>>,
+}
+
+impl KnockoutPreview {
+    pub fn new(art: &LoadedImage) -> Self {
+        Self {
+            art: art.clone(),
+            preview: Mutex::new(None),
+        }
+    }
+
+    pub fn preview(&self, shirt_color: egui::Color32, tolerance: u32) -> KnockedOut {
+        let mut preview = self.preview.lock().unwrap();
+        match preview.as_ref() {
+            Some((color, t, knocked_out)) if *color == shirt_color && *t == tolerance => {
+                knocked_out.clone()
+            }
+            _ => {
+                let image = knock_out(&self.art, shirt_color, tolerance, "knockout_preview");
+                let knocked_out = KnockedOut {
+                    opaque_percent: compute_percent_opaque(image.pixels()),
+                    image,
+                };
+                *preview = Some((shirt_color, tolerance, knocked_out.clone()));
+                knocked_out
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod knockout_should {
+    use super::*;
+
+    const NAVY: egui::Color32 = egui::Color32::from_rgb(0x1f, 0x2a, 0x44);
+
+    // Navy and orange stripes, with a navy fading out at the bottom
+    fn striped_art() -> LoadedImage {
+        let striped = |x, y| match (x, y) {
+            (_, 9) => egui::Color32::from_rgba_unmultiplied(0x1f, 0x2a, 0x44, 128),
+            (0..=4, _) => NAVY,
+            _ => egui::Color32::from_rgb(240, 140, 20),
+        };
+        load_image_from_function([10, 10], striped, "striped")
+    }
+
+    #[test]
+    fn let_the_shirt_show_through() {
+        let shirt = egui::Color32::from_rgb(0x22, 0x2c, 0x40);
+        let knocked_out = knock_out(&striped_art(), shirt, 8, "knocked_out");
+        assert_eq!(egui::Color32::TRANSPARENT, knocked_out.pixels()[0]);
+        assert_eq!(egui::Color32::TRANSPARENT, knocked_out.pixels()[90]);
+        assert_eq!(
+            egui::Color32::from_rgb(240, 140, 20),
+            knocked_out.pixels()[5]
+        );
+        // Too far from the shirt color to go
+        let knocked_out = knock_out(&striped_art(), shirt, 2, "knocked_out");
+        assert_eq!(NAVY, knocked_out.pixels()[0]);
+    }
+
+    #[test]
+    fn use_less_ink() {
+        let preview = KnockoutPreview::new(&striped_art());
+        assert_eq!(100, preview.preview(egui::Color32::RED, 8).opaque_percent);
+        assert_eq!(45, preview.preview(NAVY, 8).opaque_percent);
+    }
+}
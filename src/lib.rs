@@ -2,21 +2,48 @@
 
 mod app;
 pub use app::TShirtCheckerApp;
+mod art_placement;
+pub use art_placement::ArtPlacement;
+mod background;
+mod garment;
+pub use garment::Garment;
+mod halftone;
 mod hsla;
 pub use hsla::Hsla;
 mod loaded_image;
-pub use loaded_image::LoadedImage;
+pub use loaded_image::{load_image_from_untrusted_source, LoadedImage};
 mod image_utils;
+mod print_area;
+pub use print_area::{PrintArea, ShirtSide};
+mod report_export;
+pub use report_export::{ArtworkReport, ReportResult};
+mod report_html;
+pub use report_html::HtmlReport;
+mod report_settings;
+pub use report_settings::ReportSettings;
 mod report_templates;
+pub use report_templates::{ReportStatus, ReportTemplates, ReportThresholds, ReportTypes};
+mod shirt_colors;
+mod shirt_comparison;
+mod shirt_contrast;
+mod specks;
+pub use shirt_colors::{ShirtColor, ShirtColorCatalog};
 mod tshirt_storage;
-pub use tshirt_storage::TShirtStorage;
+pub use tshirt_storage::{shirt_color_from_hex, TShirtStorage};
 mod artwork;
+pub use artwork::ArtworkDependentData;
 mod async_tasks;
 mod error;
+mod fringe;
 mod icons;
+mod image_resolution;
+mod ink_colors;
+mod knockout;
 mod log;
+mod margins;
 mod math;
 mod movement_state;
 mod notice_panel;
 mod time;
 mod tool_select;
+mod underbase;
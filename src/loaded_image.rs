@@ -11,36 +11,54 @@
 //! I have a second use case where I need to modify existing images (i.e., change t-shirt
 //! colors).  For the sake of convenience, I'm always storing the underlying egui::ColorImage
 //!
+//! The artwork analysis code only ever looks at the pixels, so a LoadedImage is just a
+//! size and an RGBA pixel buffer.  The GPU texture is created the first time the GUI
+//! asks for it, so images that are never displayed (or are used from the command line)
+//! never touch an egui::Context.
+//!
 
 use crate::error::*;
-use std::sync::Arc;
+use crate::image_resolution::*;
+use std::sync::{Arc, OnceLock};
 
 /// My image abstraction
+#[derive(Clone)]
 pub struct LoadedImage {
     uncompressed_image: Arc<egui::ColorImage>,
-    texture: egui::TextureHandle,
+    name: String,
+    // The resolution the image file says it should be printed at, if it says
+    embedded_dpi: Option<f32>,
+    // Created on first display, then shared by every clone of the image.
+    texture: Arc<OnceLock<egui::TextureHandle>>,
 }
 
-impl Clone for LoadedImage {
-    fn clone(&self) -> Self {
-        LoadedImage {
-            uncompressed_image: self.uncompressed_image.clone(),
-            texture: self.texture.clone(),
+impl LoadedImage {
+    pub fn new(uncompressed_image: egui::ColorImage, name: impl Into<String>) -> Self {
+        Self {
+            uncompressed_image: Arc::new(uncompressed_image),
+            name: name.into(),
+            embedded_dpi: None,
+            texture: Arc::new(OnceLock::new()),
         }
     }
-}
 
-impl LoadedImage {
-    pub fn id(&self) -> egui::TextureId {
-        self.texture.id()
+    pub fn id(&self, ctx: &egui::Context) -> egui::TextureId {
+        self.texture_handle(ctx).id()
     }
 
-    pub fn texture_handle(&self) -> &egui::TextureHandle {
-        &self.texture
+    pub fn texture_handle(&self, ctx: &egui::Context) -> &egui::TextureHandle {
+        self.texture.get_or_init(|| {
+            ctx.load_texture(
+                self.name.clone(),
+                self.uncompressed_image.clone(),
+                Default::default(),
+            )
+        })
     }
 
     pub fn size(&self) -> egui::Vec2 {
-        self.texture.size_vec2()
+        let [x, y] = self.uncompressed_image.size;
+        egui::Vec2::new(x as f32, y as f32)
     }
 
     pub fn pixels(&self) -> &Vec<egui::Color32> {
@@ -50,12 +68,38 @@ impl LoadedImage {
     pub fn size_as_array(&self) -> &[usize; 2] {
         &self.uncompressed_image.size
     }
+
+    /// The resolution embedded in the image file, in dots per inch
+    pub fn embedded_dpi(&self) -> Option<f32> {
+        self.embedded_dpi
+    }
+
+    /// Encode the image as a .png file
+    pub fn encode_png(&self) -> Vec<u8> {
+        use image::ImageEncoder;
+
+        let [xsize, ysize] = *self.size_as_array();
+        let unmultiplied: Vec<u8> = self
+            .pixels()
+            .iter()
+            .flat_map(|p| p.to_srgba_unmultiplied())
+            .collect();
+        let mut png = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png)
+            .write_image(
+                &unmultiplied,
+                xsize as u32,
+                ysize as u32,
+                image::ExtendedColorType::Rgba8,
+            )
+            .unwrap();
+        png
+    }
 }
 
 pub fn load_image_from_untrusted_source(
     bytes: &[u8],
     name: impl Into<String>,
-    ctx: &egui::Context,
 ) -> Result<LoadedImage, Error> {
     // 3300 = 11 inches x 300 DPI.  Cut in half for now to avoid the 2048 hard cap
     let maybe_svg =
@@ -74,61 +118,77 @@ pub fn load_image_from_untrusted_source(
             raw_uncompressed_image_maybe.err().unwrap(),
         ));
     }
-    let uncompressed_image = Arc::new(raw_uncompressed_image_maybe.unwrap());
-    let texture: egui::TextureHandle =
-        ctx.load_texture(name, uncompressed_image.clone(), Default::default());
-    Ok(LoadedImage {
-        uncompressed_image,
-        texture,
-    })
+    let mut image = LoadedImage::new(raw_uncompressed_image_maybe.unwrap(), name);
+    image.embedded_dpi = embedded_dpi(bytes);
+    Ok(image)
 }
 
-pub fn load_image_from_trusted_source(
-    bytes: &[u8],
-    name: impl Into<String>,
-    ctx: &egui::Context,
-) -> LoadedImage {
-    load_image_from_untrusted_source(bytes, name, ctx).unwrap()
+pub fn load_image_from_trusted_source(bytes: &[u8], name: impl Into<String>) -> LoadedImage {
+    load_image_from_untrusted_source(bytes, name).unwrap()
 }
 
 pub fn load_image_from_existing_image(
     existing: &LoadedImage,
     mutator: &dyn Fn(&egui::Color32) -> egui::Color32,
     name: impl Into<String>,
-    ctx: &egui::Context,
 ) -> LoadedImage {
     let pixels = existing.pixels().iter().map(mutator).collect();
     let size = *existing.size_as_array();
-    let uncompressed_image = Arc::new(egui::ColorImage { size, pixels });
-    let texture: egui::TextureHandle =
-        ctx.load_texture(name, uncompressed_image.clone(), Default::default());
+    let mut image = LoadedImage::new(egui::ColorImage { size, pixels }, name);
+    // Same size, so the same print size.
+    image.embedded_dpi = existing.embedded_dpi;
+    image
+}
 
-    LoadedImage {
-        uncompressed_image,
-        texture,
-    }
+/// New pixels for an existing image.  The pixels must be the existing image's size.
+pub fn load_image_from_existing_size(
+    existing: &LoadedImage,
+    pixels: Vec<egui::Color32>,
+    name: impl Into<String>,
+) -> LoadedImage {
+    let size = *existing.size_as_array();
+    let mut image = LoadedImage::new(egui::ColorImage { size, pixels }, name);
+    // Same size, so the same print size.
+    image.embedded_dpi = existing.embedded_dpi;
+    image
+}
+
+/// A crop of an existing image.  The pixels are printed at the same resolution, so the
+/// embedded DPI is kept.
+pub fn load_image_from_crop(
+    existing: &LoadedImage,
+    pixels: Vec<egui::Color32>,
+    size: [usize; 2],
+    name: impl Into<String>,
+) -> LoadedImage {
+    let mut image = LoadedImage::new(egui::ColorImage { size, pixels }, name);
+    image.embedded_dpi = existing.embedded_dpi;
+    image
 }
 
 pub fn load_image_from_pixels(
     pixels: Vec<egui::Color32>,
     size: [usize; 2],
     name: impl Into<String>,
-    ctx: &egui::Context,
 ) -> LoadedImage {
-    let uncompressed_image = Arc::new(egui::ColorImage { size, pixels });
-    let texture: egui::TextureHandle =
-        ctx.load_texture(name, uncompressed_image.clone(), Default::default());
-    LoadedImage {
-        uncompressed_image,
-        texture,
-    }
+    LoadedImage::new(egui::ColorImage { size, pixels }, name)
 }
 
-pub fn heat_map_from_image(
-    existing: &LoadedImage,
+/// An image with every pixel set by a function of its x and y.  Tests use it to
+/// draw the art they check.
+#[cfg(test)]
+pub fn load_image_from_function(
+    size: [usize; 2],
+    pixel: impl Fn(usize, usize) -> egui::Color32,
     name: impl Into<String>,
-    ctx: &egui::Context,
 ) -> LoadedImage {
+    let pixels = (0..size[0] * size[1])
+        .map(|i| pixel(i % size[0], i / size[0]))
+        .collect();
+    load_image_from_pixels(pixels, size, name)
+}
+
+pub fn heat_map_from_image(existing: &LoadedImage, name: impl Into<String>) -> LoadedImage {
     let in_pixels = existing.pixels();
     //let mut old_out_pixels = Vec::new();
     let xsize = existing.size_as_array()[0];
@@ -197,15 +257,5 @@ pub fn heat_map_from_image(
         .collect();
 
     let size = [heat_x, heat_y];
-    let uncompressed_image = Arc::new(egui::ColorImage {
-        size,
-        pixels: out_pixels,
-    });
-    let texture: egui::TextureHandle =
-        ctx.load_texture(name, uncompressed_image.clone(), Default::default());
-
-    LoadedImage {
-        uncompressed_image,
-        texture,
-    }
+    load_image_from_pixels(out_pixels, size, name)
 }
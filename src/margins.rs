@@ -0,0 +1,105 @@
+//! Transparent margins
+//!
+//! Art often arrives on a canvas much bigger than the art itself, with a wide
+//! transparent margin around it.  Scaling the whole canvas to the print area would
+//! print the art smaller than it could be, and make its DPI look better than it will
+//! be once the art is sized up.  So the art is placed by its content - the rectangle
+//! around its non-transparent pixels - and the margin can be cropped off.
+
+use crate::loaded_image::*;
+
+/// The rectangle around the art's non-transparent pixels, in pixels of the art.  None
+/// if the art is completely transparent.
+pub fn content_bounds(art: &LoadedImage) -> Option<egui::Rect> {
+    let [width, _] = *art.size_as_array();
+    let mut bounds: Option<[usize; 4]> = None;
+    for (index, _) in art.pixels().iter().enumerate().filter(|(_, p)| p.a() != 0) {
+        let (x, y) = (index % width, index / width);
+        bounds = Some(match bounds {
+            None => [x, y, x, y],
+            Some([x0, y0, x1, y1]) => [x0.min(x), y0.min(y), x1.max(x), y1.max(y)],
+        });
+    }
+    let [x0, y0, x1, y1] = bounds?;
+    Some(egui::Rect::from_min_max(
+        egui::pos2(x0 as f32, y0 as f32),
+        egui::pos2(x1 as f32 + 1.0, y1 as f32 + 1.0),
+    ))
+}
+
+/// How much of the art's canvas is transparent margin
+pub struct MarginCheck {
+    content: egui::Rect,
+    margin_percent: u32,
+}
+
+impl MarginCheck {
+    pub fn new(art: &LoadedImage) -> Self {
+        let canvas = egui::Rect::from_min_size(egui::Pos2::ZERO, art.size());
+        let content = content_bounds(art).unwrap_or(canvas);
+        let canvas_area = canvas.area().max(1.0);
+        Self {
+            content,
+            margin_percent: (100.0 * (canvas_area - content.area()) / canvas_area) as u32,
+        }
+    }
+
+    /// The rectangle around the art's non-transparent pixels, in pixels of the art.
+    /// Completely transparent art is all content.
+    pub fn content(&self) -> egui::Rect {
+        self.content
+    }
+
+    /// Percentage of the canvas outside the content
+    pub fn margin_percent(&self) -> u32 {
+        self.margin_percent
+    }
+}
+
+/// The art cropped to its non-transparent pixels
+pub fn crop_margins(art: &LoadedImage, name: impl Into<String>) -> LoadedImage {
+    let [width, _] = *art.size_as_array();
+    let Some(content) = content_bounds(art) else {
+        return load_image_from_existing_size(art, art.pixels().clone(), name);
+    };
+    let (x0, y0) = (content.min.x as usize, content.min.y as usize);
+    let (x1, y1) = (content.max.x as usize, content.max.y as usize);
+    let pixels = (y0..y1)
+        .flat_map(|y| art.pixels()[x0 + y * width..x1 + y * width].iter().copied())
+        .collect();
+    load_image_from_crop(art, pixels, [x1 - x0, y1 - y0], name)
+}
+
+#[cfg(test)]
+mod margins_should {
+    use super::*;
+
+    // A 4x2 red block in the middle of a 10x10 canvas
+    fn block_with_margin() -> LoadedImage {
+        let block_with_margin = |x, y| match (x, y) {
+            (3..=6, 4..=5) => egui::Color32::RED,
+            _ => egui::Color32::TRANSPARENT,
+        };
+        load_image_from_function([10, 10], block_with_margin, "block_with_margin")
+    }
+
+    #[test]
+    fn find_the_content_inside_the_margin() {
+        let check = MarginCheck::new(&block_with_margin());
+        let content = egui::Rect::from_min_max(egui::pos2(3.0, 4.0), egui::pos2(7.0, 6.0));
+        assert_eq!(content, check.content());
+        assert_eq!(92, check.margin_percent());
+
+        let empty = load_image_from_pixels(vec![egui::Color32::TRANSPARENT; 4], [2, 2], "e");
+        assert_eq!(None, content_bounds(&empty));
+        assert_eq!(0, MarginCheck::new(&empty).margin_percent());
+    }
+
+    #[test]
+    fn crop_the_margin_off() {
+        let cropped = crop_margins(&block_with_margin(), "cropped");
+        assert_eq!(egui::vec2(4.0, 2.0), cropped.size());
+        assert!(cropped.pixels().iter().all(|p| *p == egui::Color32::RED));
+        assert_eq!(0, MarginCheck::new(&cropped).margin_percent());
+    }
+}
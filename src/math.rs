@@ -1,4 +1,5 @@
 extern crate nalgebra as na;
+use crate::print_area::PrintArea;
 use na::{matrix, vector, Matrix3, Vector3};
 
 #[derive(Debug)]
@@ -59,16 +60,20 @@ pub fn tshirt_to_display(viewport: ViewPort) -> Matrix3<f32> {
     centered_tshirt_to_display * center_at_target_and_scale
 }
 
-pub fn art_to_art_space(art_size: egui::Vec2) -> Matrix3<f32> {
-    //pub fn art_to_art_space(art: &LoadedImage) -> Matrix3<f32> {
-    // The space for the artwork is aways 11 x 14 inches
-    let artspace_size = vector!(11.0, 14.0);
+//
+// Transforms from the artwork's space, where (0,0) is the top left corner of the
+// art and (1,1) is the bottom right corner, to "t shirt artwork space".  The art is
+// scaled to fit the print area and centered.
+//
+pub fn art_to_art_space(art_size: egui::Vec2, print_area: PrintArea) -> Matrix3<f32> {
+    let print_size = print_area.size_in_inches();
+    let artspace_size = vector!(print_size.x, print_size.y);
     let artspace_aspect = artspace_size.x / artspace_size.y;
     let art_aspect = art_size.x / art_size.y;
 
     if artspace_aspect > art_aspect {
         // space for art is wider than the artwork
-        // map the art so the art's length is 14 inches
+        // map the art so the art's length is the print area's length
         // preserve the art's aspect ratio for the width
 
         let y_art_on_artspace_dim = artspace_size.y;
@@ -79,7 +84,7 @@ pub fn art_to_art_space(art_size: egui::Vec2) -> Matrix3<f32> {
                              0.0,        0.0,               1.0  ]
     } else {
         // space for art is taller than the artwork
-        // map the art so the art's width is 11 inches
+        // map the art so the art's width is the print area's width
         // preserve the art's aspect ratio for the width
         let x_art_on_artspace_dim = artspace_size.x;
         let y_art_on_artspace_dim = x_art_on_artspace_dim / art_aspect;
@@ -91,24 +96,51 @@ pub fn art_to_art_space(art_size: egui::Vec2) -> Matrix3<f32> {
 }
 
 //
-// Transforms from "t shirt artwork space", where (0,0) is
-// the top corner of the artwork and (11.0, 14.0) is the
-// bottom corner, into "t shirt" space.
+// The size the artwork will be printed at, in inches
+//
+pub fn art_size_in_inches(art_size: egui::Vec2, print_area: PrintArea) -> egui::Vec2 {
+    let art_to_inches = art_to_art_space(art_size, print_area);
+    let top_corner = art_to_inches * vector![0.0, 0.0, 1.0];
+    let bot_corner = art_to_inches * vector![1.0, 1.0, 1.0];
+    let dim_in_inches = bot_corner - top_corner;
+    egui::Vec2::new(dim_in_inches.x, dim_in_inches.y)
+}
+
+//
+// Where the print area sits on the t-shirt image by default, in "t shirt" space.
 //
-// 11.0 x 14.0 is the working area for the artwork in inches
+// The print area's width and height are in inches (11.0 x 14.0 for a full front
+// print), and an inch takes up the same amount of the t-shirt image no matter
+// what print area is selected.
 //
-pub fn art_space_to_tshirt(tshirt_size: egui::Vec2) -> Matrix3<f32> {
+pub fn print_area_on_tshirt(tshirt_size: egui::Vec2, print_area: PrintArea) -> egui::Rect {
     let tshirt_aspect = tshirt_size.x / tshirt_size.y;
 
-    let xcenter = 0.50; // center artwork mid point for X
-    let ycenter = 0.45; // center artwork 45% down for Y
+    let center = print_area.center_on_tshirt();
+    let print_size = print_area.size_in_inches();
 
-    let xarea = 0.48 / 11.0; // Artwork on 48% of the horizontal image
-                             // Artwork as 11 x 14 inches, so use that to compute y area
+    let xarea = PrintArea::tshirt_width_per_inch();
     let yarea = xarea * tshirt_aspect;
+    let size_on_tshirt = egui::Vec2::new(xarea * print_size.x, yarea * print_size.y);
 
-    matrix![         xarea,          0.0,               xcenter - xarea * 11.0 / 2.0;
-                         0.0,            yarea,             ycenter - yarea * 14.0 / 2.0;
+    egui::Rect::from_center_size(center.to_pos2(), size_on_tshirt)
+}
+
+//
+// Transforms from "t shirt artwork space", where (0,0) is
+// the top corner of the print area and (width, height) is the
+// bottom corner, onto a rectangle in "t shirt" space.
+//
+// The rectangle is usually print_area_on_tshirt, but garment mockups can have
+// their print areas calibrated by hand.
+//
+pub fn art_space_to_rect(print_area: PrintArea, rect: egui::Rect) -> Matrix3<f32> {
+    let print_size = print_area.size_in_inches();
+    let xscale = rect.width() / print_size.x;
+    let yscale = rect.height() / print_size.y;
+
+    matrix![         xscale,         0.0,               rect.min.x;
+                         0.0,            yscale,            rect.min.y;
                          0.0,            0.0,               1.0 ]
 }
 
@@ -190,12 +222,35 @@ mod art_space_to_tshirt_should {
     #[test]
     fn work_with_proportions_that_mirror_target_art() {
         // 11 x 14 tshirt dimension
-        let matrix = art_space_to_tshirt(egui::Vec2::new(2200.0, 2800.0));
+        let print_area = PrintArea::FullFront;
+        let rect = print_area_on_tshirt(egui::Vec2::new(2200.0, 2800.0), print_area);
+        let matrix = art_space_to_rect(print_area, rect);
         let top_left = matrix * vector!(0.0, 0.0, 1.0);
         let bot_right = matrix * vector!(11.0, 14.0, 1.0);
         assert_eq!(vector![0.26, 0.21, 1.0], top_left);
         assert_eq!(vector![0.74, 0.69, 1.0], bot_right);
     }
+
+    #[test]
+    fn center_smaller_print_areas_on_their_spot() {
+        let print_area = PrintArea::LeftChest;
+        let rect = print_area_on_tshirt(egui::Vec2::new(2200.0, 2800.0), print_area);
+        let matrix = art_space_to_rect(print_area, rect);
+        let size = print_area.size_in_inches();
+        let center = matrix * vector!(size.x / 2.0, size.y / 2.0, 1.0);
+        assert!((center.x - 0.63).abs() < 1e-6);
+        assert!((center.y - 0.30).abs() < 1e-6);
+    }
+
+    #[test]
+    fn map_the_print_area_onto_a_calibrated_rectangle() {
+        let rect = egui::Rect::from_min_max(egui::pos2(0.2, 0.3), egui::pos2(0.8, 0.5));
+        let matrix = art_space_to_rect(PrintArea::FullFront, rect);
+        let top_left = matrix * vector!(0.0, 0.0, 1.0);
+        let bot_right = matrix * vector!(11.0, 14.0, 1.0);
+        assert!((top_left - vector![0.2, 0.3, 1.0]).norm() < 1e-6);
+        assert!((bot_right - vector![0.8, 0.5, 1.0]).norm() < 1e-6);
+    }
 }
 
 #[cfg(test)]
@@ -204,7 +259,7 @@ mod art_to_art_size_should {
 
     #[test]
     fn work_with_wide_images() {
-        let actual = art_to_art_space(egui::Vec2::new(2200.0, 1400.0));
+        let actual = art_to_art_space(egui::Vec2::new(2200.0, 1400.0), PrintArea::FullFront);
         // 11 inches wide, 7 inches tall, 3.5 inch margin
         let expected = matrix![ 11.0, 0.0, 0.0 ;
                             0.0,   7.0, 3.5 ;
@@ -214,11 +269,18 @@ mod art_to_art_size_should {
 
     #[test]
     fn work_with_narrow_images() {
-        let actual = art_to_art_space(egui::Vec2::new(1100.0, 2800.0));
+        let actual = art_to_art_space(egui::Vec2::new(1100.0, 2800.0), PrintArea::FullFront);
         // 14 inches tall, 5.5 inches wide, 2.75 inch margin
         let expected = matrix![ 5.5, 0.0, 2.75 ;
                             0.0,   14.0, 0.0 ;
                             0.0,   0.0,    1.0 ];
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn fit_images_to_the_print_area() {
+        let actual = art_size_in_inches(egui::Vec2::new(1000.0, 500.0), PrintArea::LeftChest);
+        // 4 inches wide, 2 inches tall
+        assert_eq!(egui::Vec2::new(4.0, 2.0), actual);
+    }
 }
@@ -0,0 +1,108 @@
+//! Print area profiles
+//!
+//! Where on the T-Shirt the artwork is printed and how big the printable area is.
+//! A full front print is 11 x 14 inches, but printers also offer smaller and larger
+//! areas (left chest, pocket, oversized, etc), and some areas are on the back of the
+//! T-Shirt.
+
+/// Which side of the T-Shirt is being looked at or printed on
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ShirtSide {
+    #[default]
+    Front,
+    Back,
+}
+
+impl ShirtSide {
+    pub const ALL: [ShirtSide; 2] = [ShirtSide::Front, ShirtSide::Back];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShirtSide::Front => "Front",
+            ShirtSide::Back => "Back",
+        }
+    }
+}
+
+/// Where on the T-Shirt the artwork gets printed
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum PrintArea {
+    #[default]
+    FullFront,
+    Oversized,
+    LeftChest,
+    Youth,
+    Pocket,
+    FullBack,
+    BackYoke,
+}
+
+// How much of the t-shirt image's width one inch takes up.  The full front print
+// area is 11 inches wide and uses 48% of the image.
+//
+const TSHIRT_WIDTH_PER_INCH: f32 = 0.48 / 11.0;
+
+impl PrintArea {
+    pub const ALL: [PrintArea; 7] = [
+        PrintArea::FullFront,
+        PrintArea::Oversized,
+        PrintArea::LeftChest,
+        PrintArea::Youth,
+        PrintArea::Pocket,
+        PrintArea::FullBack,
+        PrintArea::BackYoke,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrintArea::FullFront => "Full Front",
+            PrintArea::Oversized => "Oversized",
+            PrintArea::LeftChest => "Left Chest",
+            PrintArea::Youth => "Youth",
+            PrintArea::Pocket => "Pocket",
+            PrintArea::FullBack => "Full Back",
+            PrintArea::BackYoke => "Back Yoke",
+        }
+    }
+
+    /// Size of the printable area, in inches
+    pub fn size_in_inches(&self) -> egui::Vec2 {
+        match self {
+            PrintArea::FullFront => egui::Vec2::new(11.0, 14.0),
+            PrintArea::Oversized => egui::Vec2::new(14.0, 16.0),
+            PrintArea::LeftChest => egui::Vec2::new(4.0, 4.0),
+            PrintArea::Youth => egui::Vec2::new(9.0, 11.0),
+            PrintArea::Pocket => egui::Vec2::new(3.5, 3.5),
+            PrintArea::FullBack => egui::Vec2::new(12.0, 14.0),
+            PrintArea::BackYoke => egui::Vec2::new(12.0, 3.0),
+        }
+    }
+
+    /// Center of the printable area in t-shirt space, where (0,0) is the top left
+    /// corner of the t-shirt image and (1,1) is the bottom right corner.
+    pub fn center_on_tshirt(&self) -> egui::Vec2 {
+        match self {
+            PrintArea::FullFront => egui::Vec2::new(0.50, 0.45),
+            PrintArea::Oversized => egui::Vec2::new(0.50, 0.47),
+            // The wearer's left is on the right side of the image.
+            PrintArea::LeftChest => egui::Vec2::new(0.63, 0.30),
+            PrintArea::Youth => egui::Vec2::new(0.50, 0.42),
+            PrintArea::Pocket => egui::Vec2::new(0.63, 0.32),
+            PrintArea::FullBack => egui::Vec2::new(0.50, 0.44),
+            PrintArea::BackYoke => egui::Vec2::new(0.50, 0.17),
+        }
+    }
+
+    /// The side of the T-Shirt the print area is on
+    pub fn side(&self) -> ShirtSide {
+        match self {
+            PrintArea::FullBack | PrintArea::BackYoke => ShirtSide::Back,
+            _ => ShirtSide::Front,
+        }
+    }
+
+    /// How much of the t-shirt image's width one inch takes up
+    pub fn tshirt_width_per_inch() -> f32 {
+        TSHIRT_WIDTH_PER_INCH
+    }
+}
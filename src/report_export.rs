@@ -0,0 +1,115 @@
+//! Machine readable report export
+//!
+//! Collects the results of every report for one piece of artwork, along with the
+//! numbers they were computed from, so they can be saved as JSON and attached to an
+//! order instead of someone screenshotting the right panel.
+
+use crate::art_placement::*;
+use crate::artwork::*;
+use crate::loaded_image::*;
+use crate::print_area::*;
+use crate::report_settings::*;
+use crate::report_templates::*;
+use crate::tshirt_storage::*;
+
+/// The outcome of one report
+#[derive(serde::Serialize)]
+pub struct ReportResult {
+    pub report: ReportTypes,
+    pub label: String,
+    pub metric: Option<u32>,
+    pub display_percent: bool,
+    pub status: ReportStatus,
+    pub thresholds: ReportThresholds,
+}
+
+/// The outcome of every report for one piece of artwork
+#[derive(serde::Serialize)]
+pub struct ArtworkReport {
+    pub image_width: usize,
+    pub image_height: usize,
+    pub dpi: Option<u32>,
+    /// The resolution embedded in the image file, if it has one
+    pub embedded_dpi: Option<u32>,
+    /// Set if the embedded resolution and the printed resolution disagree
+    pub embedded_dpi_warning: Option<String>,
+    pub print_area: PrintArea,
+    /// The shirt color the contrast report was computed against, as #rrggbb
+    pub shirt_color: String,
+    pub placement: ArtPlacement,
+    pub print_width_inches: f32,
+    pub print_height_inches: f32,
+    /// The ink colors needed to screen print the art, as #RRGGBB, most used first
+    pub ink_colors: Vec<String>,
+    pub reports: Vec<ReportResult>,
+}
+
+impl ArtworkReport {
+    pub fn new(
+        art: &LoadedImage,
+        placement: &ArtPlacement,
+        art_dependent_data: &ArtworkDependentData,
+        report_templates: &ReportTemplates,
+        settings: &ReportSettings,
+        shirt_color: egui::Color32,
+    ) -> Self {
+        let reports: Vec<ReportResult> = ReportTypes::ALL
+            .iter()
+            .map(|report_type| {
+                let template = report_templates.report_type_to_template(*report_type);
+                let thresholds = settings.thresholds(*report_type);
+                ReportResult {
+                    report: *report_type,
+                    // Some labels are split over two lines for the right panel.
+                    label: template.label.replace('\n', " "),
+                    metric: template.metric(art, placement, shirt_color, Some(art_dependent_data)),
+                    display_percent: template.display_percent,
+                    status: template.status(
+                        art,
+                        placement,
+                        shirt_color,
+                        Some(art_dependent_data),
+                        thresholds,
+                    ),
+                    thresholds: *thresholds,
+                }
+            })
+            .collect();
+
+        let dpi = reports
+            .iter()
+            .find(|result| result.report == ReportTypes::Dpi)
+            .and_then(|result| result.metric);
+        let [image_width, image_height] = *art.size_as_array();
+        let print_area = art_dependent_data.print_area();
+        let print_size = placement.size_in_inches(art.size());
+        let ink_colors = art_dependent_data
+            .ink_palette()
+            .inks()
+            .iter()
+            .map(|ink| {
+                let [r, g, b, _] = ink.color.to_array();
+                format!("#{:02X}{:02X}{:02X}", r, g, b)
+            })
+            .collect();
+
+        Self {
+            image_width,
+            image_height,
+            dpi,
+            embedded_dpi: art.embedded_dpi().map(|dpi| dpi.round() as u32),
+            embedded_dpi_warning: placement.embedded_dpi_warning(art),
+            print_area,
+            shirt_color: shirt_color_to_hex(shirt_color),
+            placement: *placement,
+            print_width_inches: print_size.x,
+            print_height_inches: print_size.y,
+            ink_colors,
+            reports,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
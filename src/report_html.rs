@@ -0,0 +1,221 @@
+//! Self contained HTML report
+//!
+//! A single .html file with the report table, the explanation for each report, and
+//! the report tool images embedded as .png data.  Customers can't run the app, but
+//! they can open an HTML file that's been emailed to them.
+
+use crate::artwork::*;
+use crate::image_utils::*;
+use crate::loaded_image::*;
+use crate::math::*;
+use crate::report_export::*;
+use crate::report_templates::*;
+use crate::tshirt_storage::*;
+use base64::Engine;
+
+// Width of the t-shirt mockup in pixels.  The t-shirt images are small, so they're
+// scaled up to this.
+const MOCKUP_WIDTH: usize = 800;
+
+/// Everything needed to write the HTML report.
+///
+/// Creating the report only copies the data it needs, so it's cheap enough to do on
+/// the main thread.  The expensive part (building the mockup and encoding the
+/// images) happens in to_html.
+///
+pub struct HtmlReport {
+    report: ArtworkReport,
+    report_tips: Vec<String>,
+    art: LoadedImage,
+    shirt: LoadedImage,
+    // Where the print area is on the shirt image, in t-shirt space
+    print_area_on_shirt: egui::Rect,
+    partial_transparency_problems: LoadedImage,
+    thin_line_problems: LoadedImage,
+    bib_opaque_mask: LoadedImage,
+    low_contrast_problems: LoadedImage,
+}
+
+impl HtmlReport {
+    pub fn new(
+        report: ArtworkReport,
+        art: &LoadedImage,
+        art_dependent_data: &ArtworkDependentData,
+        report_templates: &ReportTemplates,
+        shirt: &LoadedImage,
+        print_area_on_shirt: egui::Rect,
+    ) -> Self {
+        let report_tips = ReportTypes::ALL
+            .iter()
+            .map(|report_type| {
+                let template = report_templates.report_type_to_template(*report_type);
+                template.report_tip.clone()
+            })
+            .collect();
+        let shirt_color = shirt_color_from_hex(&report.shirt_color).unwrap_or_default();
+
+        Self {
+            low_contrast_problems: art_dependent_data
+                .shirt_contrast()
+                .low_contrast_problems(shirt_color),
+            report,
+            report_tips,
+            art: art.clone(),
+            shirt: shirt.clone(),
+            print_area_on_shirt,
+            partial_transparency_problems: art_dependent_data
+                .partial_transparency_problems()
+                .clone(),
+            thin_line_problems: art_dependent_data.thin_line_problems().clone(),
+            bib_opaque_mask: art_dependent_data.bib_opaque_mask().clone(),
+        }
+    }
+
+    pub fn to_html(&self) -> String {
+        let print_area = self.report.print_area;
+        let art_to_tshirt = art_space_to_rect(print_area, self.print_area_on_shirt)
+            * self.report.placement.art_to_art_space(self.art.size());
+        let mockup = mockup_on_shirt(&self.shirt, &self.art, art_to_tshirt, MOCKUP_WIDTH);
+
+        let mut html = String::new();
+        html.push_str(HTML_HEADER);
+        html.push_str("<h1>T-Shirt Art Report</h1>\n");
+        html.push_str(&format!(
+            "<p>{} x {} pixels, printed at {:.1} x {:.1} inches ({} print area).</p>\n",
+            self.report.image_width,
+            self.report.image_height,
+            self.report.print_width_inches,
+            self.report.print_height_inches,
+            print_area.name()
+        ));
+
+        if let Some(warning) = &self.report.embedded_dpi_warning {
+            html.push_str(&format!("<p class=\"Warn\">{}</p>\n", escape_html(warning)));
+        }
+
+        html.push_str("<table>\n<tr><th>Report</th><th>Result</th><th>Status</th><th>What it means</th></tr>\n");
+        for (result, tip) in self.report.reports.iter().zip(&self.report_tips) {
+            let metric = match result.metric {
+                Some(n) => format!("{}{}", n, if result.display_percent { "%" } else { "" }),
+                None => "???".to_string(),
+            };
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td class=\"{:?}\">{:?}</td><td>{}</td></tr>\n",
+                escape_html(&result.label),
+                metric,
+                result.status,
+                result.status,
+                escape_html(tip)
+            ));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str(&figure(&mockup, "The artwork on the selected T-Shirt."));
+        html.push_str(&figure(
+            &self.partial_transparency_problems,
+            "Partial transparency problems are shown with inverted colors.",
+        ));
+        html.push_str(&figure(
+            &self.thin_line_problems,
+            "Thin lines that may not survive the laundry are shown with inverted colors.",
+        ));
+        html.push_str(&figure(
+            &self.bib_opaque_mask,
+            "Areas that will be covered with ink are shown in black.",
+        ));
+        html.push_str(&figure(
+            &self.low_contrast_problems,
+            "Areas that are hard to see on the T-Shirt color are flagged in black or white.",
+        ));
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>T-Shirt Art Report</title>
+<style>
+body { font-family: sans-serif; max-width: 900px; margin: auto; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 4px 8px; vertical-align: top; text-align: left; }
+.Pass { color: #080; }
+.Warn { color: #b80; }
+.Fail { color: #c00; }
+img { max-width: 100%; background: repeating-conic-gradient(#ddd 0% 25%, #fff 0% 50%) 50% / 20px 20px; }
+</style>
+</head>
+<body>
+"#;
+
+// An embedded image with a caption
+//
+fn figure(image: &LoadedImage, caption: &str) -> String {
+    let png = base64::engine::general_purpose::STANDARD.encode(image.encode_png());
+    format!(
+        "<figure>\n<img src=\"data:image/png;base64,{}\">\n<figcaption>{}</figcaption>\n</figure>\n",
+        png,
+        escape_html(caption)
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod html_report_should {
+    use super::*;
+    use crate::art_placement::*;
+    use crate::print_area::*;
+    use crate::report_settings::*;
+
+    #[test]
+    fn include_every_report_and_embedded_image() {
+        let art = load_image_from_trusted_source(include_bytes!("../assets/tux.svg"), "art");
+        let shirt =
+            load_image_from_trusted_source(include_bytes!("../assets/blue_tshirt.png"), "shirt");
+        let settings = ReportSettings::default();
+        let art_dependent_data = async_std::task::block_on(ArtworkDependentData::new(
+            &art,
+            &settings,
+            ShirtSide::Front,
+            None,
+        ));
+        let templates = ReportTemplates::new();
+        let report = ArtworkReport::new(
+            &art,
+            &ArtPlacement::fit(art.size(), PrintArea::FullFront),
+            &art_dependent_data,
+            &templates,
+            &settings,
+            egui::Color32::BLACK,
+        );
+        let html = HtmlReport::new(
+            report,
+            &art,
+            &art_dependent_data,
+            &templates,
+            &shirt,
+            print_area_on_tshirt(shirt.size(), PrintArea::FullFront),
+        )
+        .to_html();
+
+        assert_eq!(1 + ReportTypes::ALL.len(), html.matches("<tr>").count());
+        assert!(html.contains("<td>Partial Transparency</td>"));
+        assert_eq!(5, html.matches("<img src=\"data:image/png;base64,").count());
+    }
+
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!(
+            "&lt;b&gt;Salt &amp; &quot;Pepper&quot;&lt;/b&gt;",
+            escape_html("<b>Salt & \"Pepper\"</b>")
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! User adjustable settings for the reports
+//!
+//! Different printers and print techniques have different limits, so the pass/ warn/
+//! fail thresholds for every report, the thinnest printable line, and the print area
+//! can be changed by the user.  The settings are persisted between runs of the app.
+
+use crate::halftone::*;
+use crate::image_utils::*;
+use crate::print_area::*;
+use crate::report_templates::*;
+
+/// Settings used to compute and grade the reports
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ReportSettings {
+    pub dpi: ReportThresholds,
+    pub area_used: ReportThresholds,
+    pub margins: ReportThresholds,
+    pub bib: ReportThresholds,
+    pub background: ReportThresholds,
+    pub thin_lines: ReportThresholds,
+    pub partial_transparency: ReportThresholds,
+    pub fringe: ReportThresholds,
+    pub color_count: ReportThresholds,
+    pub low_contrast: ReportThresholds,
+    pub specks: ReportThresholds,
+    /// Lines thinner than 1/thin_line_fraction of an inch are flagged by Thin Lines
+    pub thin_line_fraction: u32,
+    /// Islands of ink smaller than this, in square inches, are flagged by Specks
+    pub max_speck_area: f32,
+    /// Where on the front of the T-Shirt the art is printed
+    pub print_area: PrintArea,
+    /// Where on the back of the T-Shirt the art is printed
+    pub back_print_area: PrintArea,
+    /// How far (0 to 255, per color channel) from the background color a pixel can be
+    /// and still be removed as background
+    pub background_tolerance: u32,
+    /// How far (0 to 255, per color channel) from the shirt color a pixel can be and
+    /// still be knocked out
+    pub knockout_tolerance: u32,
+    /// Which pixels the partial transparency fix keeps
+    pub alpha_fix: AlphaFixSettings,
+    /// Dot shape, LPI and angle used by the halftone fix
+    pub halftone: HalftoneSettings,
+}
+
+impl Default for ReportSettings {
+    fn default() -> Self {
+        Self {
+            dpi: ReportThresholds {
+                higher_is_better: true,
+                pass: 300,
+                warn: 200,
+            },
+            area_used: ReportThresholds {
+                higher_is_better: true,
+                pass: 91,
+                warn: 51,
+            },
+            margins: ReportThresholds {
+                higher_is_better: false,
+                pass: 10,
+                warn: 40,
+            },
+            bib: ReportThresholds {
+                higher_is_better: false,
+                pass: 49,
+                warn: 74,
+            },
+            background: ReportThresholds {
+                higher_is_better: false,
+                pass: 20,
+                warn: 50,
+            },
+            thin_lines: ReportThresholds {
+                higher_is_better: false,
+                pass: 0,
+                warn: 0,
+            },
+            partial_transparency: ReportThresholds {
+                higher_is_better: false,
+                pass: 0,
+                warn: 0,
+            },
+            fringe: ReportThresholds {
+                higher_is_better: false,
+                pass: 5,
+                warn: 20,
+            },
+            color_count: ReportThresholds {
+                higher_is_better: false,
+                pass: 4,
+                warn: 6,
+            },
+            low_contrast: ReportThresholds {
+                higher_is_better: false,
+                pass: 10,
+                warn: 25,
+            },
+            specks: ReportThresholds {
+                higher_is_better: false,
+                pass: 0,
+                warn: 5,
+            },
+            thin_line_fraction: 64,
+            max_speck_area: 1.0 / (32.0 * 32.0),
+            print_area: PrintArea::FullFront,
+            back_print_area: PrintArea::FullBack,
+            background_tolerance: 24,
+            knockout_tolerance: 24,
+            alpha_fix: AlphaFixSettings::default(),
+            halftone: HalftoneSettings::default(),
+        }
+    }
+}
+
+impl ReportSettings {
+    pub fn thresholds(&self, report_type: ReportTypes) -> &ReportThresholds {
+        match report_type {
+            ReportTypes::Dpi => &self.dpi,
+            ReportTypes::AreaUsed => &self.area_used,
+            ReportTypes::Margins => &self.margins,
+            ReportTypes::Bib => &self.bib,
+            ReportTypes::Background => &self.background,
+            ReportTypes::ThinLines => &self.thin_lines,
+            ReportTypes::PartialTransparency => &self.partial_transparency,
+            ReportTypes::Fringe => &self.fringe,
+            ReportTypes::ColorCount => &self.color_count,
+            ReportTypes::LowContrast => &self.low_contrast,
+            ReportTypes::Specks => &self.specks,
+        }
+    }
+
+    pub fn thresholds_mut(&mut self, report_type: ReportTypes) -> &mut ReportThresholds {
+        match report_type {
+            ReportTypes::Dpi => &mut self.dpi,
+            ReportTypes::AreaUsed => &mut self.area_used,
+            ReportTypes::Margins => &mut self.margins,
+            ReportTypes::Bib => &mut self.bib,
+            ReportTypes::Background => &mut self.background,
+            ReportTypes::ThinLines => &mut self.thin_lines,
+            ReportTypes::PartialTransparency => &mut self.partial_transparency,
+            ReportTypes::Fringe => &mut self.fringe,
+            ReportTypes::ColorCount => &mut self.color_count,
+            ReportTypes::LowContrast => &mut self.low_contrast,
+            ReportTypes::Specks => &mut self.specks,
+        }
+    }
+
+    pub fn print_area_for(&self, side: ShirtSide) -> PrintArea {
+        match side {
+            ShirtSide::Front => self.print_area,
+            ShirtSide::Back => self.back_print_area,
+        }
+    }
+
+    pub fn print_area_for_mut(&mut self, side: ShirtSide) -> &mut PrintArea {
+        match side {
+            ShirtSide::Front => &mut self.print_area,
+            ShirtSide::Back => &mut self.back_print_area,
+        }
+    }
+
+    /// The thinnest line that will print, in inches
+    pub fn min_line_width(&self) -> f32 {
+        1.0 / (self.thin_line_fraction.max(1) as f32)
+    }
+
+    /// The thinnest line that will print, in pixels, for art printed at dpi
+    pub fn thin_line_pixels(&self, dpi: f32) -> usize {
+        (dpi * self.min_line_width()).ceil() as usize
+    }
+}
+
+#[cfg(test)]
+mod report_settings_should {
+    use super::*;
+
+    #[test]
+    fn fill_in_missing_settings_with_defaults() {
+        let settings: ReportSettings =
+            serde_json::from_str(r#"{ "thin_line_fraction": 32 }"#).unwrap();
+        assert_eq!(32, settings.thin_line_fraction);
+        assert_eq!(ReportSettings::default().dpi, settings.dpi);
+    }
+
+    #[test]
+    fn round_trip_through_json() {
+        let mut settings = ReportSettings::default();
+        settings.thresholds_mut(ReportTypes::Dpi).pass = 250;
+        let json = serde_json::to_string(&settings).unwrap();
+        let loaded: ReportSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(settings, loaded);
+        assert_eq!(250, loaded.thresholds(ReportTypes::Dpi).pass);
+    }
+
+    #[test]
+    fn keep_a_print_area_for_each_side() {
+        let mut settings = ReportSettings::default();
+        *settings.print_area_for_mut(ShirtSide::Back) = PrintArea::BackYoke;
+        assert_eq!(
+            PrintArea::FullFront,
+            settings.print_area_for(ShirtSide::Front)
+        );
+        assert_eq!(
+            PrintArea::BackYoke,
+            settings.print_area_for(ShirtSide::Back)
+        );
+        for side in ShirtSide::ALL {
+            assert_eq!(side, settings.print_area_for(side).side());
+        }
+    }
+}
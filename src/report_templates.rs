@@ -1,10 +1,8 @@
+use crate::art_placement::*;
 use crate::artwork::*;
-use crate::math::*;
 use crate::LoadedImage;
-extern crate nalgebra as na;
-use na::dvector;
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize)]
 pub enum ReportStatus {
     Pass,
     Warn,
@@ -12,13 +10,85 @@ pub enum ReportStatus {
     Unknown,
 }
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize)]
 pub enum ReportTypes {
     Dpi,
     PartialTransparency,
+    Fringe,
     Bib,
     AreaUsed,
+    Margins,
+    Background,
     ThinLines,
+    ColorCount,
+    LowContrast,
+    Specks,
+}
+
+impl ReportTypes {
+    /// Every report, in the order they're displayed
+    pub const ALL: [ReportTypes; 11] = [
+        ReportTypes::Dpi,
+        ReportTypes::AreaUsed,
+        ReportTypes::Margins,
+        ReportTypes::Bib,
+        ReportTypes::Background,
+        ReportTypes::ThinLines,
+        ReportTypes::Specks,
+        ReportTypes::PartialTransparency,
+        ReportTypes::Fringe,
+        ReportTypes::ColorCount,
+        ReportTypes::LowContrast,
+    ];
+
+    /// Does the report change with the color of the shirt?
+    pub fn depends_on_shirt_color(&self) -> bool {
+        matches!(self, ReportTypes::LowContrast)
+    }
+}
+
+/// Where a report's metric changes from Pass to Warn, and from Warn to Fail
+///
+/// If higher_is_better is set, metrics at or above pass pass and metrics at or above
+/// warn warn.  Otherwise metrics at or below pass pass and metrics at or below warn
+/// warn.  Everything else fails.
+///
+#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ReportThresholds {
+    pub higher_is_better: bool,
+    pub pass: u32,
+    pub warn: u32,
+}
+
+impl ReportThresholds {
+    pub fn status(&self, metric: Option<u32>) -> ReportStatus {
+        match metric {
+            None => ReportStatus::Unknown,
+            Some(n) => {
+                let (passes, warns) = if self.higher_is_better {
+                    (n >= self.pass, n >= self.warn)
+                } else {
+                    (n <= self.pass, n <= self.warn)
+                };
+                if passes {
+                    ReportStatus::Pass
+                } else if warns {
+                    ReportStatus::Warn
+                } else {
+                    ReportStatus::Fail
+                }
+            }
+        }
+    }
+
+    /// The range warn can be set to, so it's never a better score than pass
+    pub fn warn_range(&self, max: u32) -> std::ops::RangeInclusive<u32> {
+        if self.higher_is_better {
+            0..=self.pass.min(max)
+        } else {
+            self.pass.min(max)..=max
+        }
+    }
 }
 
 pub struct ReportTemplate {
@@ -26,9 +96,12 @@ pub struct ReportTemplate {
     pub report_tip: String,
     pub tool_tip: String,
     pub display_percent: bool,
-    pub metric_to_status: fn(metric: Option<u32>) -> ReportStatus,
-    pub generate_metric:
-        fn(art: &LoadedImage, art_dependent_data: Option<&ArtworkDependentData>) -> Option<u32>,
+    pub generate_metric: fn(
+        art: &LoadedImage,
+        placement: &ArtPlacement,
+        shirt_color: egui::Color32,
+        art_dependent_data: Option<&ArtworkDependentData>,
+    ) -> Option<u32>,
 }
 
 impl ReportTemplate {
@@ -39,185 +112,273 @@ impl ReportTemplate {
             "".to_string()
         }
     }
+    pub fn metric(
+        &self,
+        art: &LoadedImage,
+        placement: &ArtPlacement,
+        shirt_color: egui::Color32,
+        art_dependent_data: Option<&ArtworkDependentData>,
+    ) -> Option<u32> {
+        (self.generate_metric)(art, placement, shirt_color, art_dependent_data)
+    }
     pub fn status(
         &self,
         art: &LoadedImage,
+        placement: &ArtPlacement,
+        shirt_color: egui::Color32,
         art_dependent_data: Option<&ArtworkDependentData>,
+        thresholds: &ReportThresholds,
     ) -> ReportStatus {
-        let metric = (self.generate_metric)(art, art_dependent_data);
-        (self.metric_to_status)(metric)
+        thresholds.status(self.metric(art, placement, shirt_color, art_dependent_data))
     }
     pub fn metric_text(
         &self,
         art: &LoadedImage,
+        placement: &ArtPlacement,
+        shirt_color: egui::Color32,
         art_dependent_data: Option<&ArtworkDependentData>,
     ) -> String {
-        let metric = (self.generate_metric)(art, art_dependent_data);
-        match metric {
+        match self.metric(art, placement, shirt_color, art_dependent_data) {
             Some(n) => format!("{}", n),
             None => "???".to_string(),
         }
     }
 }
 
-fn dpi_to_status(dpi: Option<u32>) -> ReportStatus {
-    match dpi {
-        None => ReportStatus::Unknown,
-        Some(n) => match n {
-            0..=199 => ReportStatus::Fail,
-            200..=299 => ReportStatus::Warn,
-            _ => ReportStatus::Pass,
-        },
-    }
-}
-
 fn compute_dpi(
     art: &LoadedImage,
+    placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
     art_dependent_data: Option<&ArtworkDependentData>,
 ) -> Option<u32> {
+    // The DPI tool needs the hot spots in the dependent data, so wait for it.
     art_dependent_data?;
-    let top_corner = art_to_art_space(art.size()) * dvector![0.0, 0.0, 1.0];
-    let bot_corner = art_to_art_space(art.size()) * dvector![1.0, 1.0, 1.0];
-    let dim_in_inches = bot_corner - top_corner;
-    let dpi = (art.size().x / dim_in_inches.x) as u32;
-    Some(dpi)
-}
-
-fn bad_transparency_to_status(bad_transparency_pixels: Option<u32>) -> ReportStatus {
-    match bad_transparency_pixels {
-        None => ReportStatus::Unknown,
-        Some(n) => match n {
-            0 => ReportStatus::Pass,
-            _ => ReportStatus::Fail,
-        },
-    }
-}
-
-fn area_used_to_status(area_used: Option<u32>) -> ReportStatus {
-    match area_used {
-        None => ReportStatus::Unknown,
-        Some(n) => match n {
-            0..=50 => ReportStatus::Fail,
-            51..=90 => ReportStatus::Warn,
-            _ => ReportStatus::Pass,
-        },
-    }
-}
-
-fn opaque_to_status(opaque_area: Option<u32>) -> ReportStatus {
-    match opaque_area {
-        None => ReportStatus::Unknown,
-        Some(n) => match n {
-            0..=49 => ReportStatus::Pass,
-            50..=74 => ReportStatus::Warn,
-            _ => ReportStatus::Fail,
-        },
-    }
-}
-
-fn thin_line_to_status(opaque_area: Option<u32>) -> ReportStatus {
-    match opaque_area {
-        None => ReportStatus::Unknown,
-        Some(n) => match n {
-            0 => ReportStatus::Pass,
-            _ => ReportStatus::Fail,
-        },
-    }
+    Some(placement.dpi(art.size()).round() as u32)
 }
 
 fn compute_area_used(
     art: &LoadedImage,
-    _art_dependent_data: Option<&ArtworkDependentData>,
+    placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
+    art_dependent_data: Option<&ArtworkDependentData>,
 ) -> Option<u32> {
-    let top_corner = art_to_art_space(art.size()) * dvector![0.0, 0.0, 1.0];
-    let bot_corner = art_to_art_space(art.size()) * dvector![1.0, 1.0, 1.0];
-    let dim_in_inches = bot_corner - top_corner;
-    let area_used = 100.0 * dim_in_inches[0] * dim_in_inches[1] / (11.0 * 14.0);
+    // Only the art's content counts, not the transparent margin around it.
+    let art_dependent_data = art_dependent_data?;
+    let content = art_dependent_data.margin_check().content();
+    let print_area = art_dependent_data.print_area();
+    let area_used = placement.percent_of_print_area(art.size(), content.size(), print_area);
     Some(area_used as u32)
 }
 
+fn compute_margins(
+    _art: &LoadedImage,
+    _placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
+    optional_art_dependent_data: Option<&ArtworkDependentData>,
+) -> Option<u32> {
+    let art_dependent_data = optional_art_dependent_data?;
+    Some(art_dependent_data.margin_check().margin_percent())
+}
+
 fn compute_bib_score(
     art: &LoadedImage,
+    placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
+    optional_art_dependent_data: Option<&ArtworkDependentData>,
+) -> Option<u32> {
+    // The opaque percentage is of the whole canvas, margin included.
+    let art_dependent_data = optional_art_dependent_data?;
+    let print_area = art_dependent_data.print_area();
+    let canvas_used = placement.percent_of_print_area(art.size(), art.size(), print_area);
+    let bib_score = canvas_used * art_dependent_data.bib_opaque_percent() as f32 / 100.0;
+    Some(bib_score as u32)
+}
+
+fn compute_background(
+    _art: &LoadedImage,
+    _placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
     optional_art_dependent_data: Option<&ArtworkDependentData>,
 ) -> Option<u32> {
     let art_dependent_data = optional_art_dependent_data?;
-    let area_used = compute_area_used(art, Some(art_dependent_data))?;
-    let bib_score = area_used * art_dependent_data.bib_opaque_percent() / 100;
-    Some(bib_score)
+    Some(art_dependent_data.background_check().border_percent())
 }
 
 fn compute_badtransparency_pixels(
     _art: &LoadedImage,
+    _placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
     optional_art_dependent_data: Option<&ArtworkDependentData>,
 ) -> Option<u32> {
     let art_dependent_data = optional_art_dependent_data?;
     Some(art_dependent_data.partial_transparency_percent())
 }
 
+fn compute_fringe(
+    _art: &LoadedImage,
+    _placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
+    optional_art_dependent_data: Option<&ArtworkDependentData>,
+) -> Option<u32> {
+    let art_dependent_data = optional_art_dependent_data?;
+    Some(art_dependent_data.fringe_check().fringe_percent())
+}
+
 fn compute_thin_line(
     _art: &LoadedImage,
+    _placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
     optional_art_dependent_data: Option<&ArtworkDependentData>,
 ) -> Option<u32> {
     let art_dependent_data = optional_art_dependent_data?;
     Some(art_dependent_data.thin_line_percent())
 }
 
+fn compute_specks(
+    art: &LoadedImage,
+    placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
+    optional_art_dependent_data: Option<&ArtworkDependentData>,
+) -> Option<u32> {
+    let art_dependent_data = optional_art_dependent_data?;
+    Some(art_dependent_data.specks(placement.dpi(art.size())).count() as u32)
+}
+
+fn compute_color_count(
+    _art: &LoadedImage,
+    _placement: &ArtPlacement,
+    _shirt_color: egui::Color32,
+    optional_art_dependent_data: Option<&ArtworkDependentData>,
+) -> Option<u32> {
+    let art_dependent_data = optional_art_dependent_data?;
+    Some(art_dependent_data.ink_palette().inks().len() as u32)
+}
+
+fn compute_low_contrast(
+    _art: &LoadedImage,
+    _placement: &ArtPlacement,
+    shirt_color: egui::Color32,
+    optional_art_dependent_data: Option<&ArtworkDependentData>,
+) -> Option<u32> {
+    let art_dependent_data = optional_art_dependent_data?;
+    Some(
+        art_dependent_data
+            .shirt_contrast()
+            .low_contrast_percent(shirt_color),
+    )
+}
+
 pub struct ReportTemplates {
     area_used_report: ReportTemplate,
+    margins_report: ReportTemplate,
     transparency_report: ReportTemplate,
+    fringe_report: ReportTemplate,
     opaque_report: ReportTemplate,
+    background_report: ReportTemplate,
     dpi_report: ReportTemplate,
     thin_lines_report: ReportTemplate,
+    specks_report: ReportTemplate,
+    color_count_report: ReportTemplate,
+    low_contrast_report: ReportTemplate,
+}
+
+impl Default for ReportTemplates {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ReportTemplates {
     pub fn new() -> Self {
         let dpi_report = ReportTemplate {
             label: "DPI".to_string(),
-            report_tip: "Ideally, artwork for T-Shirts should be Print Quality - 300 DPI or more. Medium Quality (200 to 299 DPI) is probably okay. Below 200 DPI pixalation may be noticable.".to_string(),
+            report_tip: "Ideally, artwork for T-Shirts should be Print Quality - at or above the DPI the report passes at.  Medium Quality (between the pass and warn DPIs) is probably okay.  Below the warn DPI pixalation may be noticable.  The thresholds can be changed in the settings.".to_string(),
             tool_tip: "Show close ups of areas where artwork might look pixelly.\nTurn off the tool or move the T-Shirt to exit.".to_string(),
             display_percent: false,
-            metric_to_status: dpi_to_status,
             generate_metric: compute_dpi,
         };
         let area_used_report = ReportTemplate {
             label: "Area Used".to_string(),
-            report_tip: "Artwork is usually printed on an 11 inch by 14 inch area of the T-Shirt.  The report shows how much of that printable area the art is currently filling.  There's no rule that says art has to use all of the available area, but it's nice to know how much available area there is.".to_string(),
+            report_tip: "Artwork is printed on the selected print area of the T-Shirt (11 inches by 14 inches for a full front print).  The report shows how much of that printable area the art is currently filling, at the size it's placed at.  There's no rule that says art has to use all of the available area, but it's nice to know how much available area there is.".to_string(),
             tool_tip: "Show the maximum boundary of the printable area on the T-Shirt.".to_string(),
             display_percent: true,
-            metric_to_status: area_used_to_status,
             generate_metric: compute_area_used,
         };
+        let margins_report = ReportTemplate {
+            label: "Margins".to_string(),
+            report_tip: "Art with a wide transparent margin around it is placed by the art itself, so the margin doesn't shrink it or make its DPI look better than it is.  The report shows how much of the image is transparent margin.  Printers often size the whole image, margin and all, so crop the margin off before sending the art to be printed.".to_string(),
+            tool_tip: "Show the edge of the art inside the transparent margin.".to_string(),
+            display_percent: true,
+            generate_metric: compute_margins,
+        };
         let transparency_report = ReportTemplate {
             label: "Partial\nTransparency".to_string(),
             report_tip: "The processed used to print T-Shirt artwork doesn't support partial transparency.  Either the artwork is being printed (100% transparecy) or the T-Shirt is showing through (0% transparency) - there's nothing in between.  For best results, fix partial transparency problems in your art package of choice.".to_string(),
             tool_tip: "Show areas of the artwork where there's partial transparency of some kind.".to_string(),
             display_percent: true,
-            metric_to_status: bad_transparency_to_status,
             generate_metric: compute_badtransparency_pixels,
         };
+        let fringe_report = ReportTemplate {
+            label: "Fringe".to_string(),
+            report_tip: "Some art packages blend the soft edges of the artwork with a matte color (usually white) as well as making them partially transparent.  When the partial transparency is fixed, the matte color is left behind as a halo around the artwork - very noticeable on a dark T-Shirt.  The report shows how much of the artwork's soft edge was blended with a matte.  Defringe takes the matte back out before fixing the partial transparency.".to_string(),
+            tool_tip: "Flash the edge pixels that were blended with a matte color.".to_string(),
+            display_percent: true,
+            generate_metric: compute_fringe,
+        };
         let opaque_report = ReportTemplate {
             label: "Bib Score".to_string(),
             report_tip: "T-Shirt artwork shouldn't cover all the printable area.  The more area the artwork covers, the more the T-Shirt will feel like a pastic bib you'd put on a baby for meal time.  For best results the artwork have transparent areas where the T-Shirt will show through, and work with the T-Shirt color.".to_string(),
             tool_tip: "TODO: have tool do something.".to_string(),
             display_percent: true,
-            metric_to_status: opaque_to_status,
             generate_metric: compute_bib_score,
         };
+        let background_report = ReportTemplate {
+            label: "Background".to_string(),
+            report_tip: "The artwork looks like it still has its background - the edge of the image is opaque and nearly all one color.  That usually means the art was saved without transparency (i.e., as a JPEG on a white box), and the whole box will be printed on the T-Shirt as a solid rectangle.  The report shows how much of the edge of the image is background.  Remove the background in your art package of choice, or with Remove background.".to_string(),
+            tool_tip: "Outline the background and flash it on the T-Shirt.".to_string(),
+            display_percent: true,
+            generate_metric: compute_background,
+        };
         let thin_lines_report = ReportTemplate {
             label: "Thin Lines".to_string(),
             report_tip: "The T-Shirt shouldn't have thin lines or small splotches of non-opaque artwork.  The concern is that the art won't survive the laundry".to_string(),
             tool_tip: "Shows problem areas in the artwork.".to_string(),
             display_percent: true,
-            metric_to_status: thin_line_to_status,
             generate_metric: compute_thin_line,
         };
+        let specks_report = ReportTemplate {
+            label: "Specks".to_string(),
+            report_tip: "Tiny islands of ink, like stray pixels an eraser missed or dust in a scan, are too small to bond to the T-Shirt and flake off in the wash.  The report counts the islands of artwork smaller than the smallest printable area.".to_string(),
+            tool_tip: "Circle the specks in the artwork.".to_string(),
+            display_percent: false,
+            generate_metric: compute_specks,
+        };
+        let color_count_report = ReportTemplate {
+            label: "Color Count".to_string(),
+            report_tip: "Screen printing uses a separate screen and ink for every color in the artwork, so every extra color adds to the cost of the print.  The report counts the distinct ink colors in the opaque parts of the artwork, ignoring the in between colors anti-aliasing leaves along edges.".to_string(),
+            tool_tip: "Show the ink colors in the artwork, and highlight where each one is printed.".to_string(),
+            display_percent: false,
+            generate_metric: compute_color_count,
+        };
+        let low_contrast_report = ReportTemplate {
+            label: "Low Contrast".to_string(),
+            report_tip: "Artwork that's close to the color of the T-Shirt disappears when it's printed - think navy on black or yellow on white.  The report shows how much of the artwork has too little contrast with the selected T-Shirt color to stand out.  Try a different T-Shirt color, or lighten or darken those parts of the artwork.".to_string(),
+            tool_tip: "Flash the areas of the artwork that are hard to see on the selected T-Shirt color.".to_string(),
+            display_percent: true,
+            generate_metric: compute_low_contrast,
+        };
         Self {
             area_used_report,
+            margins_report,
+            background_report,
+            color_count_report,
+            low_contrast_report,
             dpi_report,
+            fringe_report,
             opaque_report,
             transparency_report,
             thin_lines_report,
+            specks_report,
         }
     }
 
@@ -225,9 +386,142 @@ impl ReportTemplates {
         match report_type {
             ReportTypes::Dpi => &self.dpi_report,
             ReportTypes::AreaUsed => &self.area_used_report,
+            ReportTypes::Margins => &self.margins_report,
             ReportTypes::PartialTransparency => &self.transparency_report,
+            ReportTypes::Fringe => &self.fringe_report,
             ReportTypes::Bib => &self.opaque_report,
+            ReportTypes::Background => &self.background_report,
             ReportTypes::ThinLines => &self.thin_lines_report,
+            ReportTypes::Specks => &self.specks_report,
+            ReportTypes::ColorCount => &self.color_count_report,
+            ReportTypes::LowContrast => &self.low_contrast_report,
         }
     }
 }
+
+#[cfg(test)]
+mod report_thresholds_should {
+    use super::*;
+    use crate::report_settings::*;
+
+    #[test]
+    fn grade_metrics_where_higher_is_better() {
+        let dpi = ReportSettings::default().dpi;
+        assert_eq!(ReportStatus::Unknown, dpi.status(None));
+        assert_eq!(ReportStatus::Fail, dpi.status(Some(199)));
+        assert_eq!(ReportStatus::Warn, dpi.status(Some(200)));
+        assert_eq!(ReportStatus::Warn, dpi.status(Some(299)));
+        assert_eq!(ReportStatus::Pass, dpi.status(Some(300)));
+    }
+
+    #[test]
+    fn grade_metrics_where_lower_is_better() {
+        let bib = ReportSettings::default().bib;
+        assert_eq!(ReportStatus::Pass, bib.status(Some(49)));
+        assert_eq!(ReportStatus::Warn, bib.status(Some(50)));
+        assert_eq!(ReportStatus::Warn, bib.status(Some(74)));
+        assert_eq!(ReportStatus::Fail, bib.status(Some(75)));
+
+        let thin_lines = ReportSettings::default().thin_lines;
+        assert_eq!(ReportStatus::Pass, thin_lines.status(Some(0)));
+        assert_eq!(ReportStatus::Fail, thin_lines.status(Some(1)));
+    }
+
+    #[test]
+    fn keep_warn_on_the_failing_side_of_pass() {
+        let dpi = ReportSettings::default().dpi;
+        assert_eq!(0..=300, dpi.warn_range(2400));
+        let bib = ReportSettings::default().bib;
+        assert_eq!(49..=100, bib.warn_range(100));
+    }
+}
+
+#[cfg(test)]
+mod report_metrics_should {
+    use super::*;
+    use crate::loaded_image::*;
+    use crate::print_area::*;
+    use crate::report_settings::*;
+
+    #[test]
+    fn follow_the_size_the_art_is_placed_at() {
+        let art = load_image_from_trusted_source(include_bytes!("../assets/tux.svg"), "art");
+        let settings = ReportSettings::default();
+        let art_dependent_data = async_std::task::block_on(ArtworkDependentData::new(
+            &art,
+            &settings,
+            ShirtSide::Front,
+            None,
+        ));
+        let templates = ReportTemplates::new();
+        let metric = |report_type: ReportTypes, placement: &ArtPlacement| {
+            templates
+                .report_type_to_template(report_type)
+                .metric(
+                    &art,
+                    placement,
+                    egui::Color32::BLACK,
+                    Some(&art_dependent_data),
+                )
+                .unwrap()
+        };
+
+        let fit = ArtPlacement::fit(art.size(), PrintArea::FullFront);
+        let mut half_size = fit;
+        half_size.width /= 2.0;
+        half_size.rotation = 30.0;
+
+        let dpi = metric(ReportTypes::Dpi, &fit);
+        assert!(metric(ReportTypes::Dpi, &half_size).abs_diff(2 * dpi) <= 1);
+        let area_used = metric(ReportTypes::AreaUsed, &fit);
+        assert!(metric(ReportTypes::AreaUsed, &half_size).abs_diff(area_used / 4) <= 1);
+        assert!(metric(ReportTypes::Bib, &half_size) <= metric(ReportTypes::Bib, &fit));
+    }
+
+    #[test]
+    fn count_specks_at_the_dpi_the_art_is_placed_at() {
+        // A 4x4 dot and a 20x20 dot, well away from each other.
+        let art = load_image_from_function(
+            [400, 400],
+            |x, y| {
+                let in_square = |left: usize, size: usize| {
+                    (left..left + size).contains(&x) && (left..left + size).contains(&y)
+                };
+                if in_square(50, 4) || in_square(300, 20) {
+                    egui::Color32::BLACK
+                } else {
+                    egui::Color32::TRANSPARENT
+                }
+            },
+            "dots",
+        );
+        let settings = ReportSettings::default();
+        let art_dependent_data = async_std::task::block_on(ArtworkDependentData::new(
+            &art,
+            &settings,
+            ShirtSide::Front,
+            None,
+        ));
+        let specks = |dpi: f32| {
+            let placement = ArtPlacement::at_dpi(art.size(), dpi, PrintArea::FullFront);
+            ReportTemplates::new()
+                .report_type_to_template(ReportTypes::Specks)
+                .metric(
+                    &art,
+                    &placement,
+                    egui::Color32::BLACK,
+                    Some(&art_dependent_data),
+                )
+                .unwrap()
+        };
+
+        assert_eq!(0, specks(50.0));
+        assert_eq!(1, specks(200.0));
+        assert_eq!(2, specks(800.0));
+
+        // The thin line data is only good for the size it was computed at.
+        assert!(art_dependent_data.computed_with(&settings, None));
+        let placed = ArtPlacement::at_dpi(art.size(), 800.0, PrintArea::FullFront);
+        assert!(!art_dependent_data.computed_with(&settings, Some(placed)));
+    }
+}
@@ -0,0 +1,108 @@
+//! Shirt color catalog
+//!
+//! The t-shirts in the selection panel come from a catalog of named colors, i.e.,
+//! the colors a blank vendor sells.  A default catalog is bundled with the app, and
+//! users can load their own from a JSON file:
+//!
+//! ```json
+//! { "colors": [
+//!     { "name": "Sport Grey", "hex": "#9a9a9a", "heather": true },
+//!     { "name": "Navy", "hex": "#1f2a44" }
+//! ] }
+//! ```
+
+use crate::error::*;
+use crate::tshirt_storage::*;
+
+/// One named shirt color
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ShirtColor {
+    pub name: String,
+    pub hex: String,
+    /// Heathered fabric is a mix of fibres, so it gets a mottled texture
+    #[serde(default)]
+    pub heather: bool,
+}
+
+impl ShirtColor {
+    pub fn color(&self) -> egui::Color32 {
+        shirt_color_from_hex(&self.hex).unwrap_or(egui::Color32::GRAY)
+    }
+}
+
+/// A list of shirt colors
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ShirtColorCatalog {
+    pub colors: Vec<ShirtColor>,
+}
+
+impl Default for ShirtColorCatalog {
+    fn default() -> Self {
+        Self::from_json(include_str!("../assets/shirt_colors.json")).unwrap()
+    }
+}
+
+impl ShirtColorCatalog {
+    /// Read a catalog, checking that it has colors and that every color is valid.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let catalog: Self = serde_json::from_str(json).map_err(|e| {
+            Error::new(
+                ErrorTypes::CatalogLoadError,
+                format!("Shirt color catalog is not valid: {}", e),
+            )
+        })?;
+        if catalog.colors.is_empty() {
+            return Err(Error::new(
+                ErrorTypes::CatalogLoadError,
+                "Shirt color catalog has no colors",
+            ));
+        }
+        if let Some(bad) = catalog
+            .colors
+            .iter()
+            .find(|color| shirt_color_from_hex(&color.hex).is_none())
+        {
+            return Err(Error::new(
+                ErrorTypes::CatalogLoadError,
+                format!("Shirt color {} has a bad hex color: {}", bad.name, bad.hex),
+            ));
+        }
+        Ok(catalog)
+    }
+}
+
+#[cfg(test)]
+mod shirt_color_catalog_should {
+    use super::*;
+
+    #[test]
+    fn load_the_bundled_catalog() {
+        let catalog = ShirtColorCatalog::default();
+        assert!(catalog.colors.len() >= 6);
+        assert!(catalog.colors.iter().any(|color| color.heather));
+    }
+
+    #[test]
+    fn default_heather_to_false() {
+        let catalog = ShirtColorCatalog::from_json(
+            r##"{ "colors": [ { "name": "Navy", "hex": "#1f2a44" } ] }"##,
+        )
+        .unwrap();
+        assert!(!catalog.colors[0].heather);
+        assert_eq!(
+            egui::Color32::from_rgb(0x1f, 0x2a, 0x44),
+            catalog.colors[0].color()
+        );
+    }
+
+    #[test]
+    fn reject_bad_catalogs() {
+        assert!(ShirtColorCatalog::from_json("not json").is_err());
+        assert!(ShirtColorCatalog::from_json(r#"{ "colors": [] }"#).is_err());
+        let bad_hex = r##"{ "colors": [ { "name": "Oops", "hex": "#12" } ] }"##;
+        assert_eq!(
+            ErrorTypes::CatalogLoadError,
+            ShirtColorCatalog::from_json(bad_hex).unwrap_err().id()
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Shirt comparison
+//!
+//! "Which blank should this go on?"  Some reports (i.e., Low Contrast) depend on the
+//! color of the shirt as well as the art.  Scoring a piece of art on every shirt
+//! color lets the app show the choices side by side and suggest the best one.
+
+use crate::art_placement::*;
+use crate::artwork::*;
+use crate::loaded_image::*;
+use crate::report_settings::*;
+use crate::report_templates::*;
+use crate::tshirt_storage::*;
+
+/// How one shirt-color dependent report came out on one shirt
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ShirtReport {
+    pub report: ReportTypes,
+    pub metric: Option<u32>,
+    pub status: ReportStatus,
+}
+
+/// How a piece of art does on one shirt
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShirtScore {
+    pub shirt: TShirtColors,
+    pub reports: Vec<ShirtReport>,
+}
+
+// Worst to best, so the worst status can be found with max
+fn status_rank(status: ReportStatus) -> u32 {
+    match status {
+        ReportStatus::Pass => 0,
+        ReportStatus::Warn => 1,
+        ReportStatus::Fail => 2,
+        ReportStatus::Unknown => 3,
+    }
+}
+
+impl ShirtScore {
+    /// The worst status of any of the reports
+    pub fn status(&self) -> ReportStatus {
+        self.reports
+            .iter()
+            .map(|report| report.status)
+            .max_by_key(|status| status_rank(*status))
+            .unwrap_or(ReportStatus::Pass)
+    }
+
+    // Lower is better.  Shirts are compared on their worst status first, then on how
+    // far every metric is from the bad end of its thresholds.
+    //
+    fn rank(&self, settings: &ReportSettings) -> (u32, i64) {
+        let metrics = self
+            .reports
+            .iter()
+            .map(|report| {
+                let metric = report.metric.unwrap_or(0) as i64;
+                if settings.thresholds(report.report).higher_is_better {
+                    -metric
+                } else {
+                    metric
+                }
+            })
+            .sum();
+        (status_rank(self.status()), metrics)
+    }
+}
+
+/// Score the art on each shirt, given as the shirt and its fabric color.
+pub fn score_shirts(
+    art: &LoadedImage,
+    placement: &ArtPlacement,
+    art_dependent_data: &ArtworkDependentData,
+    report_templates: &ReportTemplates,
+    settings: &ReportSettings,
+    shirts: &[(TShirtColors, egui::Color32)],
+) -> Vec<ShirtScore> {
+    shirts
+        .iter()
+        .map(|(shirt, shirt_color)| ShirtScore {
+            shirt: *shirt,
+            reports: ReportTypes::ALL
+                .iter()
+                .filter(|report_type| report_type.depends_on_shirt_color())
+                .map(|report_type| {
+                    let template = report_templates.report_type_to_template(*report_type);
+                    let dd = Some(art_dependent_data);
+                    ShirtReport {
+                        report: *report_type,
+                        metric: template.metric(art, placement, *shirt_color, dd),
+                        status: template.status(
+                            art,
+                            placement,
+                            *shirt_color,
+                            dd,
+                            settings.thresholds(*report_type),
+                        ),
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// The best scoring shirt.  Ties go to the first shirt.
+pub fn best_shirt(scores: &[ShirtScore], settings: &ReportSettings) -> Option<TShirtColors> {
+    scores
+        .iter()
+        .min_by_key(|score| score.rank(settings))
+        .map(|score| score.shirt)
+}
+
+#[cfg(test)]
+mod shirt_comparison_should {
+    use super::*;
+    use crate::print_area::*;
+
+    #[test]
+    fn suggest_the_shirt_the_art_stands_out_on() {
+        // Tux is mostly black and white, so it disappears on black and white shirts.
+        let art = load_image_from_trusted_source(include_bytes!("../assets/tux.svg"), "art");
+        let settings = ReportSettings::default();
+        let art_dependent_data = async_std::task::block_on(ArtworkDependentData::new(
+            &art,
+            &settings,
+            ShirtSide::Front,
+            None,
+        ));
+        let shirts = [
+            (TShirtColors::Catalog(0), egui::Color32::BLACK),
+            (TShirtColors::Catalog(1), egui::Color32::RED),
+            (TShirtColors::Catalog(2), egui::Color32::WHITE),
+        ];
+        let scores = score_shirts(
+            &art,
+            &ArtPlacement::fit(art.size(), PrintArea::FullFront),
+            &art_dependent_data,
+            &ReportTemplates::new(),
+            &settings,
+            &shirts,
+        );
+
+        assert_eq!(3, scores.len());
+        assert_eq!(ReportStatus::Fail, scores[0].status());
+        assert_eq!(ReportStatus::Pass, scores[1].status());
+        assert_eq!(
+            Some(TShirtColors::Catalog(1)),
+            best_shirt(&scores, &settings)
+        );
+        assert_eq!(None, best_shirt(&[], &settings));
+    }
+}
@@ -0,0 +1,164 @@
+//! Ink versus shirt contrast
+//!
+//! Art that's close to the color of the shirt disappears when it's printed - navy
+//! on black, yellow on white.  Contrast is measured the way the WCAG measures text
+//! contrast, as a ratio of relative luminances, since it's lightness differences
+//! that keep a print readable from across a room.
+//!
+//! The shirt color changes a lot more often than the art, so only a luminance
+//! histogram of the art is kept, and the report is worked out from it whenever it's
+//! asked for.
+
+use crate::loaded_image::*;
+use std::sync::Mutex;
+
+// Ink with a contrast ratio below this against the shirt is hard to see
+const MIN_CONTRAST_RATIO: f32 = 2.0;
+const LUMINANCE_BUCKETS: usize = 1024;
+// Shirts darker than this (i.e., navy, charcoal, black) count as dark shirts
+const DARK_SHIRT_LUMINANCE: f32 = 0.18;
+
+/// Relative luminance of a color, from 0 (black) to 1 (white)
+pub fn relative_luminance(color: egui::Color32) -> f32 {
+    let linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.r()) + 0.7152 * linear(color.g()) + 0.0722 * linear(color.b())
+}
+
+/// Is the shirt color dark enough that light art stands out on it?
+pub fn is_dark(shirt_color: egui::Color32) -> bool {
+    relative_luminance(shirt_color) <= DARK_SHIRT_LUMINANCE
+}
+
+/// WCAG contrast ratio between two relative luminances, from 1 (none) to 21
+pub fn contrast_ratio(luminance_0: f32, luminance_1: f32) -> f32 {
+    let lighter = luminance_0.max(luminance_1);
+    let darker = luminance_0.min(luminance_1);
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+fn bucket_of(luminance: f32) -> usize {
+    ((luminance * LUMINANCE_BUCKETS as f32) as usize).min(LUMINANCE_BUCKETS - 1)
+}
+
+fn bucket_luminance(bucket: usize) -> f32 {
+    (bucket as f32 + 0.5) / LUMINANCE_BUCKETS as f32
+}
+
+fn is_low_contrast(luminance: f32, shirt_luminance: f32) -> bool {
+    contrast_ratio(luminance, shirt_luminance) < MIN_CONTRAST_RATIO
+}
+
+/// How the art's opaque pixels contrast with a shirt color
+pub struct ShirtContrast {
+    art: LoadedImage,
+    // Opaque pixels, bucketed by their relative luminance
+    luminance_counts: Vec<u64>,
+    opaque: u64,
+    // The last shirt color the low contrast problems were flagged for
+    problems: Mutex<Option<(egui::Color32, LoadedImage)>>,
+}
+
+impl ShirtContrast {
+    pub fn new(art: &LoadedImage) -> Self {
+        let mut luminance_counts = vec![0; LUMINANCE_BUCKETS];
+        for pixel in art.pixels().iter().filter(|pixel| pixel.a() == 255) {
+            luminance_counts[bucket_of(relative_luminance(*pixel))] += 1;
+        }
+        Self {
+            art: art.clone(),
+            opaque: luminance_counts.iter().sum(),
+            luminance_counts,
+            problems: Mutex::new(None),
+        }
+    }
+
+    /// Percentage of the opaque art that's hard to see on the shirt color
+    pub fn low_contrast_percent(&self, shirt_color: egui::Color32) -> u32 {
+        let shirt_luminance = relative_luminance(shirt_color);
+        let low_contrast: u64 = self
+            .luminance_counts
+            .iter()
+            .enumerate()
+            .filter(|(bucket, _)| is_low_contrast(bucket_luminance(*bucket), shirt_luminance))
+            .map(|(_, count)| count)
+            .sum();
+        (100 * low_contrast / self.opaque.max(1)) as u32
+    }
+
+    /// The art with the pixels that are hard to see on the shirt color flagged, in
+    /// black on light shirts and white on dark ones.
+    pub fn low_contrast_problems(&self, shirt_color: egui::Color32) -> LoadedImage {
+        let mut problems = self.problems.lock().unwrap();
+        match problems.as_ref() {
+            Some((color, image)) if *color == shirt_color => image.clone(),
+            _ => {
+                let shirt_luminance = relative_luminance(shirt_color);
+                let flag = if is_dark(shirt_color) {
+                    egui::Color32::WHITE
+                } else {
+                    egui::Color32::BLACK
+                };
+                let image = load_image_from_existing_image(
+                    &self.art,
+                    &|pixel: &egui::Color32| {
+                        let luminance = bucket_luminance(bucket_of(relative_luminance(*pixel)));
+                        if pixel.a() == 255 && is_low_contrast(luminance, shirt_luminance) {
+                            flag
+                        } else {
+                            *pixel
+                        }
+                    },
+                    "low_contrast_problems",
+                );
+                *problems = Some((shirt_color, image.clone()));
+                image
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod shirt_contrast_should {
+    use super::*;
+
+    const NAVY: egui::Color32 = egui::Color32::from_rgb(0x1f, 0x2a, 0x44);
+
+    // Top half navy, bottom half white
+    fn navy_and_white_art() -> LoadedImage {
+        let navy_and_white = |_, y| if y < 5 { NAVY } else { egui::Color32::WHITE };
+        load_image_from_function([10, 10], navy_and_white, "navy_and_white")
+    }
+
+    #[test]
+    fn measure_contrast_like_the_wcag() {
+        assert!((contrast_ratio(0.0, 1.0) - 21.0).abs() < 1e-4);
+        assert_eq!(1.0, contrast_ratio(0.5, 0.5));
+        assert!(relative_luminance(egui::Color32::YELLOW) > 0.9);
+    }
+
+    #[test]
+    fn follow_the_shirt_color() {
+        let contrast = ShirtContrast::new(&navy_and_white_art());
+        assert_eq!(50, contrast.low_contrast_percent(egui::Color32::BLACK));
+        assert_eq!(50, contrast.low_contrast_percent(egui::Color32::WHITE));
+        assert_eq!(0, contrast.low_contrast_percent(egui::Color32::RED));
+    }
+
+    #[test]
+    fn flag_the_pixels_that_disappear() {
+        let contrast = ShirtContrast::new(&navy_and_white_art());
+        let on_black = contrast.low_contrast_problems(egui::Color32::BLACK);
+        assert_eq!(egui::Color32::WHITE, on_black.pixels()[0]);
+        assert_eq!(egui::Color32::WHITE, on_black.pixels()[99]);
+        let on_white = contrast.low_contrast_problems(egui::Color32::WHITE);
+        assert_eq!(NAVY, on_white.pixels()[0]);
+        assert_eq!(egui::Color32::BLACK, on_white.pixels()[99]);
+    }
+}
@@ -0,0 +1,139 @@
+//! Speck detection
+//!
+//! Tiny islands of ink - a stray pixel the eraser missed, the dot of noise around a
+//! scanned drawing - are too small to bond to the fabric, and flake off in the wash.
+//! The Thin Lines report looks for strokes, so it doesn't catch them.  Specks are
+//! found by splitting the art into connected islands of printed pixels and keeping
+//! the ones smaller than the smallest printable area.
+
+use crate::loaded_image::*;
+
+/// One island of ink that's too small to print
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Speck {
+    /// The center of the speck, in pixels
+    pub center: egui::Pos2,
+    /// The distance from the center to the furthest corner of the speck, in pixels
+    pub radius: f32,
+    /// How many pixels are in the speck
+    pub pixels: usize,
+}
+
+// Every island in the art.  Each pixel is labeled with the index of its island, with
+// u32::MAX for transparent pixels.  Pixels touching on a side or a corner are in the
+// same island.
+//
+fn label_islands(art: &LoadedImage) -> (Vec<u32>, Vec<(usize, [usize; 4])>) {
+    let [width, height] = *art.size_as_array();
+    let pixels = art.pixels();
+    let mut labels = vec![u32::MAX; width * height];
+    // Each island's pixel count and bounding box (min x, min y, max x, max y)
+    let mut islands = Vec::new();
+    let mut stack = Vec::new();
+
+    for start in 0..width * height {
+        if pixels[start].a() == 0 || labels[start] != u32::MAX {
+            continue;
+        }
+        let label = islands.len() as u32;
+        let mut count = 0;
+        let mut bounds = [usize::MAX, usize::MAX, 0, 0];
+        labels[start] = label;
+        stack.push(start);
+        while let Some(index) = stack.pop() {
+            let (x, y) = (index % width, index / width);
+            count += 1;
+            bounds = [
+                bounds[0].min(x),
+                bounds[1].min(y),
+                bounds[2].max(x),
+                bounds[3].max(y),
+            ];
+            for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+                for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                    let neighbour = nx + ny * width;
+                    if pixels[neighbour].a() != 0 && labels[neighbour] == u32::MAX {
+                        labels[neighbour] = label;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+        islands.push((count, bounds));
+    }
+    (labels, islands)
+}
+
+/// The islands of ink smaller than max_pixels
+pub fn find_specks(art: &LoadedImage, max_pixels: usize) -> Vec<Speck> {
+    let (_, islands) = label_islands(art);
+    islands
+        .into_iter()
+        .filter(|(count, _)| *count < max_pixels)
+        .map(|(pixels, [x0, y0, x1, y1])| {
+            let min = egui::pos2(x0 as f32, y0 as f32);
+            let max = egui::pos2(x1 as f32 + 1.0, y1 as f32 + 1.0);
+            Speck {
+                center: min + (max - min) / 2.0,
+                radius: (max - min).length() / 2.0,
+                pixels,
+            }
+        })
+        .collect()
+}
+
+/// The art with the islands of ink smaller than max_pixels erased
+pub fn remove_specks(art: &LoadedImage, max_pixels: usize, name: impl Into<String>) -> LoadedImage {
+    let (labels, islands) = label_islands(art);
+    let pixels = art
+        .pixels()
+        .iter()
+        .zip(labels)
+        .map(|(pixel, label)| match islands.get(label as usize) {
+            Some((count, _)) if *count < max_pixels => egui::Color32::TRANSPARENT,
+            _ => *pixel,
+        })
+        .collect();
+    load_image_from_existing_size(art, pixels, name)
+}
+
+#[cfg(test)]
+mod specks_should {
+    use super::*;
+
+    // A 10x10 block with a pixel touching its corner, a 2x2 speck, and a faint stray
+    // pixel
+    fn block_and_specks() -> LoadedImage {
+        let block_and_specks = |x, y| match (x, y) {
+            (2..=11, 2..=11) | (20..=21, 20..=21) | (12, 12) => egui::Color32::RED,
+            (28, 3) => egui::Color32::from_black_alpha(40),
+            _ => egui::Color32::TRANSPARENT,
+        };
+        load_image_from_function([32, 32], block_and_specks, "block_and_specks")
+    }
+
+    #[test]
+    fn find_small_islands_of_ink() {
+        let specks = find_specks(&block_and_specks(), 8);
+        assert_eq!(2, specks.len());
+        assert_eq!(egui::pos2(28.5, 3.5), specks[0].center);
+        assert_eq!(1, specks[0].pixels);
+        assert_eq!(egui::pos2(21.0, 21.0), specks[1].center);
+        assert_eq!(4, specks[1].pixels);
+        // The pixel touching the block's corner is part of the block.
+        assert_eq!(3, find_specks(&block_and_specks(), 200).len());
+        assert_eq!(0, find_specks(&block_and_specks(), 1).len());
+    }
+
+    #[test]
+    fn remove_only_the_specks() {
+        let art = block_and_specks();
+        let fixed = remove_specks(&art, 8, "fixed");
+        let at = |x: usize, y: usize| fixed.pixels()[x + y * 32];
+        assert_eq!(egui::Color32::TRANSPARENT, at(20, 20));
+        assert_eq!(egui::Color32::TRANSPARENT, at(28, 3));
+        assert_eq!(egui::Color32::RED, at(5, 5));
+        assert_eq!(egui::Color32::RED, at(12, 12));
+        assert!(find_specks(&fixed, 8).is_empty());
+    }
+}
@@ -1,71 +1,433 @@
+use crate::garment::*;
 use crate::image_utils::*;
 use crate::loaded_image::*;
+use crate::math::*;
+use crate::print_area::*;
+use crate::shirt_colors::*;
 
-#[derive(PartialEq, Copy, Clone)]
+// The most custom colors that are kept around.  Adding more forgets the oldest.
+//
+const MAX_CUSTOM_COLORS: usize = 6;
+
+/// Which t-shirt is selected - an entry in the color catalog, a custom color, or an
+/// imported garment mockup
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub enum TShirtColors {
-    Red,
-    DRed,
-    Green,
-    DGreen,
-    Blue,
-    DBlue,
+    Catalog(usize),
+    Custom(egui::Color32),
+    Garment(usize),
+}
+
+/// A color to dye the blue t-shirt, and whether the fabric is heathered
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ShirtDye {
+    pub color: egui::Color32,
+    pub heathered: bool,
+}
+
+/// The front and back views of one t-shirt color
+#[derive(Clone)]
+pub struct TShirtImages {
+    front: LoadedImage,
+    back: LoadedImage,
+}
+
+impl TShirtImages {
+    /// Make a t-shirt by dyeing the blue t-shirt.  Recoloring full size photos is
+    /// slow, so it's done by an asyncronous task.
+    pub fn recolor(blue_shirt: &TShirtImages, dye: ShirtDye) -> Self {
+        let mutator = blue_to_color(dye.color);
+        let recolor_one = |blue: &LoadedImage| {
+            let recolored = load_image_from_existing_image(blue, &mutator, "recolored_shirt");
+            if dye.heathered {
+                heather(&recolored, blue, "heathered_shirt")
+            } else {
+                recolored
+            }
+        };
+        Self {
+            front: recolor_one(&blue_shirt.front),
+            back: recolor_one(&blue_shirt.back),
+        }
+    }
+
+    fn side(&self, side: ShirtSide) -> &LoadedImage {
+        match side {
+            ShirtSide::Front => &self.front,
+            ShirtSide::Back => &self.back,
+        }
+    }
 }
 
 pub struct TShirtStorage {
-    blue_t_shirt: LoadedImage,
-    red_t_shirt: LoadedImage,
-    dgreen_t_shirt: LoadedImage,
-    burg_t_shirt: LoadedImage,
-    dblue_t_shirt: LoadedImage,
-    ddgreen_t_shirt: LoadedImage,
+    // The t-shirt photo every other color is made from
+    blue_t_shirt: TShirtImages,
+    // The color catalog
+    catalog: ShirtColorCatalog,
+    // User picked colors, oldest first
+    custom_colors: Vec<egui::Color32>,
+    // Every t-shirt that's been recolored so far.  Until a t-shirt is, the blue
+    // t-shirt is shown in its place.
+    recolored: Vec<(ShirtDye, TShirtImages)>,
+    // T-Shirts an asyncronous task is recoloring
+    recoloring: Vec<ShirtDye>,
+    // Imported garment mockups, in the order they were imported
+    garments: Vec<Garment>,
 }
 
-impl TShirtStorage {
-    pub fn new(ctx: &egui::Context) -> Self {
-        let blue_shirt: LoadedImage = load_image_from_trusted_source(
-            include_bytes!("../assets/blue_tshirt.png"),
-            "blue_shirt",
-            ctx,
-        );
-        let red_mutator = blue_to_red();
-        let red_shirt: LoadedImage =
-            load_image_from_existing_image(&blue_shirt, &red_mutator, "red_shirt", ctx);
-        let dgreen_mutator = blue_to_dgreen();
-        let dgreen_shirt: LoadedImage =
-            load_image_from_existing_image(&blue_shirt, &dgreen_mutator, "dgreen_shirt", ctx);
-        let ddgreen_mutator = blue_to_ddgreen();
-        let ddgreen_shirt: LoadedImage =
-            load_image_from_existing_image(&blue_shirt, &ddgreen_mutator, "ddgreen_shirt", ctx);
-        let dblue_mutator = blue_to_dblue();
-        let dblue_shirt: LoadedImage =
-            load_image_from_existing_image(&blue_shirt, &dblue_mutator, "dblue_shirt", ctx);
-
-        let burg_mutator = blue_to_burg();
-        let burg_shirt: LoadedImage =
-            load_image_from_existing_image(&blue_shirt, &burg_mutator, "burg_shirt", ctx);
+impl Default for TShirtStorage {
+    fn default() -> Self {
+        Self::new(ShirtColorCatalog::default())
+    }
+}
 
+impl TShirtStorage {
+    pub fn new(catalog: ShirtColorCatalog) -> Self {
+        let blue_t_shirt = TShirtImages {
+            front: load_image_from_trusted_source(
+                include_bytes!("../assets/blue_tshirt.png"),
+                "blue_shirt",
+            ),
+            back: load_image_from_trusted_source(
+                include_bytes!("../assets/blue_tshirt_back.png"),
+                "blue_shirt_back",
+            ),
+        };
         Self {
-            blue_t_shirt: blue_shirt,
-            red_t_shirt: red_shirt,
-            dgreen_t_shirt: dgreen_shirt,
-            burg_t_shirt: burg_shirt,
-            dblue_t_shirt: dblue_shirt,
-            ddgreen_t_shirt: ddgreen_shirt,
+            blue_t_shirt,
+            catalog,
+            custom_colors: Vec::new(),
+            recolored: Vec::new(),
+            recoloring: Vec::new(),
+            garments: Vec::new(),
+        }
+    }
+
+    /// Replace the color catalog.  Its t-shirts are recolored later.
+    pub fn set_catalog(&mut self, catalog: ShirtColorCatalog) {
+        self.catalog = catalog;
+        self.forget_unused_t_shirts();
+    }
+
+    pub fn blue_t_shirt(&self) -> &TShirtImages {
+        &self.blue_t_shirt
+    }
+
+    /// The dyes of t-shirts that haven't been recolored or handed out to be
+    /// recolored.  They're marked as being recolored, so they're only handed out once.
+    pub fn take_t_shirts_to_recolor(&mut self) -> Vec<ShirtDye> {
+        let mut dyes = Vec::new();
+        for dye in self.dyes_in_use() {
+            let known = |d: &ShirtDye| *d == dye;
+            let done = self.recolored.iter().any(|(d, _)| known(d));
+            if !done && !self.recoloring.iter().any(known) && !dyes.iter().any(known) {
+                dyes.push(dye);
+            }
+        }
+        self.recoloring.extend(dyes.iter().copied());
+        dyes
+    }
+
+    /// Store a t-shirt an asyncronous task recolored
+    pub fn add_recolored_t_shirt(&mut self, dye: ShirtDye, images: TShirtImages) {
+        self.recoloring.retain(|d| *d != dye);
+        if self.dyes_in_use().contains(&dye) && !self.recolored.iter().any(|(d, _)| *d == dye) {
+            self.recolored.push((dye, images));
+        }
+    }
+
+    // The dyes of the catalog colors and custom colors
+    //
+    fn dyes_in_use(&self) -> Vec<ShirtDye> {
+        let catalog = self.catalog.colors.iter().map(|shirt| ShirtDye {
+            color: shirt.color(),
+            heathered: shirt.heather,
+        });
+        let custom = self.custom_colors.iter().map(|color| ShirtDye {
+            color: *color,
+            heathered: false,
+        });
+        catalog.chain(custom).collect()
+    }
+
+    fn forget_unused_t_shirts(&mut self) {
+        let in_use = self.dyes_in_use();
+        self.recolored.retain(|(dye, _)| in_use.contains(dye));
+    }
+
+    // The dye for a catalog or custom t-shirt
+    //
+    fn dye(&self, color: TShirtColors) -> Option<ShirtDye> {
+        match color {
+            TShirtColors::Catalog(index) => self.catalog.colors.get(index).map(|shirt| ShirtDye {
+                color: shirt.color(),
+                heathered: shirt.heather,
+            }),
+            TShirtColors::Custom(custom) => {
+                self.custom_colors.contains(&custom).then_some(ShirtDye {
+                    color: custom,
+                    heathered: false,
+                })
+            }
+            TShirtColors::Garment(_) => None,
+        }
+    }
+
+    pub fn catalog(&self) -> &ShirtColorCatalog {
+        &self.catalog
+    }
+
+    /// The image for a t-shirt.  Garment mockups only have one photo, so it's used
+    /// for both sides.
+    pub fn tshirt_enum_to_image(&self, color: TShirtColors, side: ShirtSide) -> &LoadedImage {
+        if let TShirtColors::Garment(index) = color {
+            if let Some(garment) = self.garments.get(index) {
+                return garment.image();
+            }
+        }
+        let images = self.dye(color).and_then(|dye| {
+            self.recolored
+                .iter()
+                .find(|(d, _)| *d == dye)
+                .map(|(_, images)| images)
+        });
+        images.unwrap_or(&self.blue_t_shirt).side(side)
+    }
+
+    /// A user facing name for the t-shirt color
+    pub fn tshirt_enum_to_name(&self, color: TShirtColors) -> String {
+        match color {
+            TShirtColors::Catalog(index) => self
+                .catalog
+                .colors
+                .get(index)
+                .map(|shirt| shirt.name.clone())
+                .unwrap_or_default(),
+            TShirtColors::Custom(custom) => shirt_color_to_hex(custom),
+            TShirtColors::Garment(index) => self
+                .garments
+                .get(index)
+                .map(|garment| garment.name().to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Add a custom color.  Does nothing if the color has already been added.
+    pub fn add_custom_color(&mut self, color: egui::Color32) {
+        if self.custom_colors.contains(&color) {
+            return;
+        }
+        if self.custom_colors.len() >= MAX_CUSTOM_COLORS {
+            self.custom_colors.remove(0);
         }
+        self.custom_colors.push(color);
+        self.forget_unused_t_shirts();
     }
 
-    pub fn tshirt_enum_to_image(&self, color: TShirtColors) -> &LoadedImage {
+    pub fn remove_custom_color(&mut self, color: egui::Color32) {
+        self.custom_colors.retain(|custom| *custom != color);
+        self.forget_unused_t_shirts();
+    }
+
+    /// The custom colors that have been added, oldest first
+    pub fn custom_colors(&self) -> Vec<egui::Color32> {
+        self.custom_colors.clone()
+    }
+
+    /// Add an imported garment mockup, returning the t-shirt that shows it
+    pub fn add_garment(&mut self, garment: Garment) -> TShirtColors {
+        self.garments.push(garment);
+        TShirtColors::Garment(self.garments.len() - 1)
+    }
+
+    pub fn remove_garment(&mut self, index: usize) {
+        if index < self.garments.len() {
+            self.garments.remove(index);
+        }
+    }
+
+    pub fn garments(&self) -> &[Garment] {
+        &self.garments
+    }
+
+    pub fn garment_mut(&mut self, index: usize) -> Option<&mut Garment> {
+        self.garments.get_mut(index)
+    }
+
+    /// Every t-shirt - the catalog colors, then the custom colors, then the garments
+    pub fn all_tshirts(&self) -> Vec<TShirtColors> {
+        let catalog = (0..self.catalog.colors.len()).map(TShirtColors::Catalog);
+        let custom = self.custom_colors().into_iter().map(TShirtColors::Custom);
+        let garments = (0..self.garments.len()).map(TShirtColors::Garment);
+        catalog.chain(custom).chain(garments).collect()
+    }
+
+    /// The fabric color of a t-shirt
+    pub fn shirt_color(&self, color: TShirtColors) -> egui::Color32 {
+        match color {
+            TShirtColors::Catalog(index) => self
+                .catalog
+                .colors
+                .get(index)
+                .map(|shirt| shirt.color())
+                .unwrap_or(egui::Color32::GRAY),
+            TShirtColors::Custom(custom) => custom,
+            TShirtColors::Garment(index) => self
+                .garments
+                .get(index)
+                .map(|garment| garment.color())
+                .unwrap_or(egui::Color32::GRAY),
+        }
+    }
+
+    pub fn tshirt_image_size(&self, color: TShirtColors) -> egui::Vec2 {
+        // Any recolored t-shirt will do, but garments have their own photos.
+        self.tshirt_enum_to_image(color, ShirtSide::Front).size()
+    }
+
+    /// Where the print area is on the t-shirt image, in t-shirt space.  Garments may
+    /// have been calibrated.
+    pub fn print_area_rect(&self, color: TShirtColors, print_area: PrintArea) -> egui::Rect {
         match color {
-            TShirtColors::Red => &self.red_t_shirt,
-            TShirtColors::DRed => &self.burg_t_shirt,
-            TShirtColors::Green => &self.dgreen_t_shirt,
-            TShirtColors::DGreen => &self.ddgreen_t_shirt,
-            TShirtColors::Blue => &self.blue_t_shirt,
-            TShirtColors::DBlue => &self.dblue_t_shirt,
+            TShirtColors::Garment(index) if index < self.garments.len() => {
+                self.garments[index].print_area_rect(print_area)
+            }
+            _ => print_area_on_tshirt(self.tshirt_image_size(color), print_area),
         }
     }
+}
+
+/// Parse a shirt color written as hex, i.e., "#1f3a5c" or "1f3a5c"
+pub fn shirt_color_from_hex(text: &str) -> Option<egui::Color32> {
+    let digits = text.trim().trim_start_matches('#');
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+    Some(egui::Color32::from_rgb(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+    ))
+}
 
-    pub fn tshirt_image_size(&self) -> egui::Vec2 {
-        self.blue_t_shirt.size() // any shirt will do.
+/// Write a shirt color as hex, i.e., "#1f3a5c"
+pub fn shirt_color_to_hex(color: egui::Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+#[cfg(test)]
+mod tshirt_storage_should {
+    use super::*;
+
+    #[test]
+    fn round_trip_colors_through_hex() {
+        let color = egui::Color32::from_rgb(0x1f, 0x3a, 0x5c);
+        assert_eq!("#1f3a5c", shirt_color_to_hex(color));
+        assert_eq!(Some(color), shirt_color_from_hex("#1f3a5c"));
+        assert_eq!(Some(color), shirt_color_from_hex(" 1F3A5C "));
+        assert_eq!(None, shirt_color_from_hex("#1f3a5"));
+        assert_eq!(None, shirt_color_from_hex("#1f3a5g"));
+    }
+
+    #[test]
+    fn keep_a_limited_list_of_custom_colors() {
+        let mut storage = TShirtStorage::default();
+        for gray in 0..(MAX_CUSTOM_COLORS as u8 + 2) {
+            storage.add_custom_color(egui::Color32::from_gray(gray));
+        }
+        storage.add_custom_color(egui::Color32::from_gray(3));
+        let colors = storage.custom_colors();
+        assert_eq!(MAX_CUSTOM_COLORS, colors.len());
+        assert_eq!(egui::Color32::from_gray(2), colors[0]);
+
+        storage.remove_custom_color(egui::Color32::from_gray(2));
+        assert!(!storage
+            .custom_colors()
+            .contains(&egui::Color32::from_gray(2)));
+    }
+
+    #[test]
+    fn recolor_each_t_shirt_once() {
+        let mut storage = TShirtStorage::default();
+        let shirt = TShirtColors::Catalog(0);
+        let blue = storage
+            .blue_t_shirt()
+            .side(ShirtSide::Front)
+            .pixels()
+            .clone();
+        assert_eq!(
+            &blue,
+            storage
+                .tshirt_enum_to_image(shirt, ShirtSide::Front)
+                .pixels()
+        );
+
+        let dyes = storage.take_t_shirts_to_recolor();
+        assert_eq!(storage.catalog().colors.len(), dyes.len());
+        assert!(storage.take_t_shirts_to_recolor().is_empty());
+
+        let images = TShirtImages::recolor(storage.blue_t_shirt(), dyes[0]);
+        storage.add_recolored_t_shirt(dyes[0], images);
+        assert_ne!(
+            &blue,
+            storage
+                .tshirt_enum_to_image(shirt, ShirtSide::Front)
+                .pixels()
+        );
+
+        let custom = egui::Color32::from_rgb(0x1f, 0x3a, 0x5c);
+        storage.add_custom_color(custom);
+        let dyes = storage.take_t_shirts_to_recolor();
+        assert_eq!(
+            vec![ShirtDye {
+                color: custom,
+                heathered: false
+            }],
+            dyes
+        );
+    }
+
+    #[test]
+    fn use_the_calibrated_print_area_for_garments() {
+        let mut storage = TShirtStorage::default();
+        let bytes = include_bytes!("../assets/blue_tshirt_back.png").to_vec();
+        let mut garment = Garment::from_image_file("Tote Bag", bytes).unwrap();
+        let rect = egui::Rect::from_min_max(egui::pos2(0.2, 0.2), egui::pos2(0.8, 0.8));
+        garment.calibration_mut().set(PrintArea::FullFront, rect);
+        let tote_bag = storage.add_garment(garment);
+        assert_eq!(Some(&tote_bag), storage.all_tshirts().last());
+
+        assert_eq!("Tote Bag", storage.tshirt_enum_to_name(tote_bag));
+        assert_eq!(
+            rect,
+            storage.print_area_rect(tote_bag, PrintArea::FullFront)
+        );
+        let shirt = TShirtColors::Catalog(0);
+        assert_ne!(rect, storage.print_area_rect(shirt, PrintArea::FullFront));
+
+        storage.remove_garment(0);
+        assert!(storage.garments().is_empty());
+    }
+
+    #[test]
+    fn name_shirts_from_the_catalog() {
+        let mut storage = TShirtStorage::default();
+        let catalog = ShirtColorCatalog::from_json(
+            r##"{ "colors": [ { "name": "Daisy", "hex": "#f8e050" } ] }"##,
+        )
+        .unwrap();
+        storage.set_catalog(catalog);
+        assert_eq!(
+            "Daisy",
+            storage.tshirt_enum_to_name(TShirtColors::Catalog(0))
+        );
+        assert_eq!("", storage.tshirt_enum_to_name(TShirtColors::Catalog(1)));
+        assert_eq!(
+            "#1f3a5c",
+            storage.tshirt_enum_to_name(TShirtColors::Custom(egui::Color32::from_rgb(
+                0x1f, 0x3a, 0x5c
+            )))
+        );
     }
 }
@@ -0,0 +1,153 @@
+//! White underbase for direct to garment (DTG) printing
+//!
+//! DTG inks are translucent.  On a dark shirt the printer lays down a layer of white
+//! ink first (the underbase), or the colors come out muddy - the shirt shows through
+//! the ink.  The underbase goes under every pixel that's printed, pulled in (choked)
+//! a little so it doesn't peek out around the edges of the art.
+//!
+//! The preview simulates the print both ways, so it's easy to see why the underbase
+//! matters, and the mask can be exported for the printer's RIP software.
+
+use crate::loaded_image::*;
+use std::sync::Mutex;
+
+// How far the underbase is pulled in from the edges of the art, in pixels
+const UNDERBASE_CHOKE: usize = 1;
+// How much of the ink's own color survives when it's printed straight on the shirt
+const INK_OPACITY: f32 = 0.25;
+
+/// White wherever the underbase is printed, transparent elsewhere.  Only pixels
+/// that are printed (fully opaque) get an underbase.
+pub fn underbase_mask(art: &LoadedImage, name: impl Into<String>) -> LoadedImage {
+    let [width, height] = *art.size_as_array();
+    let pixels = art.pixels();
+    let printed = |x: usize, y: usize| pixels[y * width + x].a() == 255;
+    let choked = |x: usize, y: usize| {
+        const C: usize = UNDERBASE_CHOKE;
+        x >= C
+            && y >= C
+            && x + C < width
+            && y + C < height
+            && (y - C..=y + C).all(|ny| (x - C..=x + C).all(|nx| printed(nx, ny)))
+    };
+    let mask = (0..width * height)
+        .map(|i| match choked(i % width, i / width) {
+            true => egui::Color32::WHITE,
+            false => egui::Color32::TRANSPARENT,
+        })
+        .collect();
+    load_image_from_pixels(mask, [width, height], name)
+}
+
+// Ink printed straight on the shirt.  The shirt's color multiplies the ink's, with a
+// little of the ink's own color left over.
+//
+fn ink_on_shirt(ink: egui::Color32, shirt: egui::Color32) -> egui::Color32 {
+    let mix = |ink: u8, shirt: u8| {
+        let ink = ink as f32;
+        let multiplied = ink * shirt as f32 / 255.0;
+        (multiplied + (ink - multiplied) * INK_OPACITY).round() as u8
+    };
+    egui::Color32::from_rgb(
+        mix(ink.r(), shirt.r()),
+        mix(ink.g(), shirt.g()),
+        mix(ink.b(), shirt.b()),
+    )
+}
+
+/// Simulated DTG prints of the art, with and without an underbase
+pub struct UnderbasePreview {
+    art: LoadedImage,
+    mask: LoadedImage,
+    // The last previews made, by shirt color and whether there's an underbase
+    previews: Mutex<Vec<(egui::Color32, bool, LoadedImage)>>,
+}
+
+impl UnderbasePreview {
+    pub fn new(art: &LoadedImage) -> Self {
+        Self {
+            art: art.clone(),
+            mask: underbase_mask(art, "underbase_mask"),
+            previews: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn mask(&self) -> &LoadedImage {
+        &self.mask
+    }
+
+    /// The art as it would print on a shirt of this color.  Ink over the underbase
+    /// keeps its color, ink straight on the shirt is muddied by it.
+    pub fn preview(&self, shirt_color: egui::Color32, with_underbase: bool) -> LoadedImage {
+        let mut previews = self.previews.lock().unwrap();
+        if let Some((_, _, image)) = previews
+            .iter()
+            .find(|(color, with, _)| *color == shirt_color && *with == with_underbase)
+        {
+            return image.clone();
+        }
+
+        let pixels = self
+            .art
+            .pixels()
+            .iter()
+            .zip(self.mask.pixels())
+            .map(|(ink, underbase)| {
+                if ink.a() < 255 {
+                    egui::Color32::TRANSPARENT
+                } else if with_underbase && underbase.a() > 0 {
+                    *ink
+                } else {
+                    ink_on_shirt(*ink, shirt_color)
+                }
+            })
+            .collect();
+        let image = load_image_from_pixels(pixels, *self.art.size_as_array(), "dtg_preview");
+
+        // One shirt color at a time, with and without the underbase
+        previews.retain(|(color, _, _)| *color == shirt_color);
+        previews.push((shirt_color, with_underbase, image.clone()));
+        image
+    }
+}
+
+#[cfg(test)]
+mod underbase_should {
+    use super::*;
+
+    const ORANGE: egui::Color32 = egui::Color32::from_rgb(240, 140, 20);
+
+    // A 6x6 orange square on a transparent 8x8 image
+    fn square_art() -> LoadedImage {
+        let square = |x, y| match (x, y) {
+            (1..=6, 1..=6) => ORANGE,
+            _ => egui::Color32::TRANSPARENT,
+        };
+        load_image_from_function([8, 8], square, "square")
+    }
+
+    #[test]
+    fn choke_the_underbase_inside_the_art() {
+        let mask = underbase_mask(&square_art(), "mask");
+        let covered = |x: usize, y: usize| mask.pixels()[y * 8 + x] == egui::Color32::WHITE;
+        assert!(covered(2, 2) && covered(5, 5));
+        assert!(!covered(1, 1) && !covered(6, 3) && !covered(0, 0));
+        assert_eq!(16, mask.pixels().iter().filter(|p| p.a() > 0).count());
+    }
+
+    #[test]
+    fn muddy_ink_printed_without_an_underbase() {
+        let preview = UnderbasePreview::new(&square_art());
+        let navy = egui::Color32::from_rgb(20, 30, 60);
+        let without = preview.preview(navy, false);
+        let with = preview.preview(navy, true);
+
+        // The middle of the art only keeps its color with an underbase
+        assert_eq!(ORANGE, with.pixels()[3 * 8 + 3]);
+        let muddy = without.pixels()[3 * 8 + 3];
+        assert!(muddy.r() < ORANGE.r() / 2 && muddy.g() < ORANGE.g() / 2);
+        // The choked edge is printed straight on the shirt either way
+        assert_eq!(without.pixels()[8 + 1], with.pixels()[8 + 1]);
+        assert_eq!(egui::Color32::TRANSPARENT, with.pixels()[0]);
+    }
+}